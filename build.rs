@@ -0,0 +1,83 @@
+//! Generates one test per day, part and example in `tests/fixtures`, which `tests/examples.rs`
+//! includes. An example is a `dayNtest.txt` or `dayNtestK.txt` input next to a
+//! `<stem>.answers.txt` file with one `<part>: <answer>` line per part it has an answer for.
+//! Examples of 2023 sit in `tests/fixtures` itself and those of other years in a subdirectory
+//! named after the year.
+
+use std::fmt::Write;
+use std::path::Path;
+
+const FIXTURES: &str = "tests/fixtures";
+const DEFAULT_YEAR: i32 = 2023;
+
+/// The stems of the examples in `dir` that have answers, sorted.
+fn answer_stems(dir: &Path) -> Vec<String> {
+    let mut stems: Vec<String> = Vec::new();
+    if let Ok(entries) = std::fs::read_dir(dir) {
+        for entry in entries.flatten() {
+            let name = entry.file_name().to_string_lossy().to_string();
+            if let Some(stem) = name.strip_suffix(".answers.txt") {
+                stems.push(stem.to_string());
+            }
+        }
+    }
+    stems.sort();
+    return stems;
+}
+
+fn main() {
+    println!("cargo:rerun-if-changed={}", FIXTURES);
+
+    let mut years = vec![DEFAULT_YEAR];
+    if let Ok(entries) = std::fs::read_dir(FIXTURES) {
+        for entry in entries.flatten() {
+            let year = entry.file_name().to_string_lossy().parse::<i32>();
+            if let (Ok(year), true) = (year, entry.path().is_dir()) {
+                if year != DEFAULT_YEAR {
+                    println!("cargo:rerun-if-changed={}", entry.path().display());
+                    years.push(year);
+                }
+            }
+        }
+    }
+    years.sort();
+
+    let mut tests = String::new();
+    for year in years {
+        let dir = match year {
+            DEFAULT_YEAR => Path::new(FIXTURES).to_path_buf(),
+            year => Path::new(FIXTURES).join(year.to_string()),
+        };
+        let prefix = match year {
+            DEFAULT_YEAR => String::new(),
+            year => format!("y{}_", year),
+        };
+        write_tests(&mut tests, year, &prefix, &dir);
+    }
+
+    let out = Path::new(&std::env::var("OUT_DIR").unwrap()).join("example_tests.rs");
+    std::fs::write(out, tests).unwrap();
+}
+
+/// Writes a test for each part of each example in `dir`, named after the example and the part.
+fn write_tests(tests: &mut String, year: i32, prefix: &str, dir: &Path) {
+    for stem in answer_stems(dir) {
+        let answers = std::fs::read_to_string(dir.join(format!("{}.answers.txt", stem))).unwrap();
+        for line in answers.lines() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let Some((part, _)) = line.split_once(':') else {
+                continue;
+            };
+            let part = part.trim();
+            writeln!(
+                tests,
+                "#[test]\nfn {prefix}{stem}_part{part}() {{\n    \
+                 check_example({year}, \"{stem}\", {part});\n}}\n"
+            )
+            .unwrap();
+        }
+    }
+}
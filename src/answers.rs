@@ -0,0 +1,84 @@
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use crate::input;
+use crate::solve::Answer;
+
+/// Expected answers for one input file, keyed by part number.
+///
+/// Stored next to the input as `<stem>.answers.txt` in the directory of its year, one
+/// `<part>: <answer>` line per part.
+/// Blank lines and lines starting with `#` are ignored.
+pub type Answers = BTreeMap<i32, Answer>;
+
+pub fn answers_path(year: i32, stem: &str) -> PathBuf {
+    return input::year_dir(year).join(format!("{}.answers.txt", stem));
+}
+
+pub fn parse_answers(text: &str) -> Result<Answers, String> {
+    let mut answers = Answers::new();
+    for (i, line) in text.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let Some((part, answer)) = line.split_once(':') else {
+            return Err(format!("line {}: expected `<part>: <answer>`", i + 1));
+        };
+        let part = match part.trim().parse::<i32>() {
+            Ok(part @ 1..=2) => part,
+            _ => return Err(format!("line {}: invalid part `{}`", i + 1, part.trim())),
+        };
+        if answers.insert(part, answer.parse().unwrap()).is_some() {
+            return Err(format!(
+                "line {}: duplicate answer for part {}",
+                i + 1,
+                part
+            ));
+        }
+    }
+    return Ok(answers);
+}
+
+pub fn format_answers(answers: &Answers) -> String {
+    return answers
+        .iter()
+        .map(|(part, answer)| format!("{}: {}\n", part, answer))
+        .collect();
+}
+
+/// Reads the stored answers at `path`. A missing file means no answers are known yet.
+pub fn read_answers(path: &Path) -> Result<Answers, String> {
+    return match std::fs::read_to_string(path) {
+        Ok(text) => parse_answers(&text).map_err(|err| format!("{}: {}", path.display(), err)),
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(Answers::new()),
+        Err(err) => Err(format!("{}: {}", path.display(), err)),
+    };
+}
+
+pub fn write_answers(path: &Path, answers: &Answers) -> std::io::Result<()> {
+    return std::fs::write(path, format_answers(answers));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_answers() {
+        let answers = parse_answers("# day 5\n1: 35\n\n2:46\n").unwrap();
+        assert_eq!(answers.get(&1), Some(&Answer::from(35)));
+        assert_eq!(answers.get(&2), Some(&Answer::from(46)));
+
+        assert!(parse_answers("3: 1").is_err());
+        assert!(parse_answers("1 35").is_err());
+        assert!(parse_answers("1: 35\n1: 36").is_err());
+    }
+
+    #[test]
+    fn test_format_answers() {
+        let answers = Answers::from([(2, Answer::from(46)), (1, Answer::from("JQK"))]);
+        assert_eq!(format_answers(&answers), "1: JQK\n2: 46\n");
+        assert_eq!(parse_answers(&format_answers(&answers)).unwrap(), answers);
+    }
+}
@@ -0,0 +1,327 @@
+use std::collections::BTreeMap;
+use std::panic::{self, AssertUnwindSafe};
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+use crate::input::{InputKind, InputSource};
+use crate::pool;
+use crate::registry::{PuzzleInfo, DEFAULT_YEAR};
+use crate::runner::{self, format_duration, Status};
+use crate::solve::{Answer, Puzzle, PuzzleError};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Stats {
+    pub min: Duration,
+    pub median: Duration,
+    pub p95: Duration,
+    pub mean: Duration,
+}
+impl Stats {
+    pub fn from_samples(samples: &[Duration]) -> Stats {
+        assert!(!samples.is_empty());
+        let mut sorted = samples.to_vec();
+        sorted.sort();
+
+        // Nearest-rank percentile, so every reported value is an actual sample.
+        let percentile = |p: usize| sorted[(sorted.len() * p).div_ceil(100).max(1) - 1];
+        return Stats {
+            min: sorted[0],
+            median: percentile(50),
+            p95: percentile(95),
+            mean: sorted.iter().sum::<Duration>() / sorted.len() as u32,
+        };
+    }
+
+    fn values(&self) -> [(&'static str, Duration); 4] {
+        return [
+            ("min", self.min),
+            ("median", self.median),
+            ("p95", self.p95),
+            ("mean", self.mean),
+        ];
+    }
+}
+
+/// Solves `part` of `puzzle` `warmup` times without measuring, then `iterations` more times,
+/// returning the last answer and the timing statistics of the measured runs. Stops at the first
+/// error, since an input the puzzle rejects is not worth timing.
+pub fn bench(
+    puzzle: &dyn Puzzle,
+    part: i32,
+    document: &str,
+    warmup: usize,
+    iterations: usize,
+) -> Result<(Answer, Stats), PuzzleError> {
+    for _ in 0..warmup {
+        runner::solve_part(puzzle, part, document)?;
+    }
+
+    let mut answer = None;
+    let mut samples: Vec<Duration> = Vec::with_capacity(iterations);
+    for _ in 0..iterations {
+        let start = Instant::now();
+        answer = Some(runner::solve_part(puzzle, part, document)?);
+        samples.push(start.elapsed());
+    }
+    return Ok((answer.unwrap(), Stats::from_samples(&samples)));
+}
+
+#[derive(Debug, Clone)]
+pub struct BenchRun {
+    pub year: i32,
+    pub day: i32,
+    pub part: i32,
+    pub input: InputKind,
+    /// The answer and timings, or why the part could not be benchmarked.
+    pub result: Result<(Answer, Stats), String>,
+}
+
+/// Benchmarks both parts of every given puzzle on its real input, running up to `jobs` benchmarks
+/// at once and returning them in order. Concurrent benchmarks compete for the CPU, so timings are
+/// only comparable between sweeps using the same number of jobs.
+pub fn bench_all(
+    puzzles: &[&'static PuzzleInfo],
+    warmup: usize,
+    iterations: usize,
+    jobs: usize,
+) -> Vec<BenchRun> {
+    let tasks: Vec<(&'static PuzzleInfo, i32)> = puzzles
+        .iter()
+        .flat_map(|&info| info.parts().into_iter().map(move |part| (info, part)))
+        .collect();
+
+    let hook = panic::take_hook();
+    panic::set_hook(Box::new(|_| {}));
+    let runs = pool::map_ordered(&tasks, jobs, |&(info, part)| {
+        let input = InputKind::Real;
+        let result = match InputSource::for_day(info.year, info.day, input).read() {
+            Ok(document) => {
+                let puzzle = info.puzzle();
+                match panic::catch_unwind(AssertUnwindSafe(|| {
+                    bench(puzzle.as_ref(), part, &document, warmup, iterations)
+                })) {
+                    Ok(result) => result.map_err(|err| format!("error: {}", err)),
+                    Err(payload) => Err(format!(
+                        "panicked: {}",
+                        runner::panic_message(payload.as_ref())
+                    )),
+                }
+            }
+            Err(err) if err.is_not_found() => Err(Status::MissingInput.to_string()),
+            Err(err) => Err(format!("error: {}", err)),
+        };
+        return BenchRun {
+            year: info.year,
+            day: info.day,
+            part,
+            input,
+            result,
+        };
+    });
+    panic::set_hook(hook);
+    return runs;
+}
+
+pub fn format_bench_table(runs: &[BenchRun]) -> String {
+    let header = [
+        "Day", "Part", "Input", "Answer", "Min", "Median", "P95", "Mean", "Status",
+    ];
+    let rows: Vec<Vec<String>> = runs
+        .iter()
+        .map(|run| {
+            let mut row = vec![
+                run.day.to_string(),
+                run.part.to_string(),
+                run.input.to_string(),
+            ];
+            match &run.result {
+                Ok((answer, stats)) => {
+                    row.push(answer.to_string());
+                    row.extend(stats.values().map(|(_, value)| format_duration(value)));
+                    row.push("ok".to_string());
+                }
+                Err(err) => {
+                    row.extend(std::iter::repeat_n(String::new(), 5));
+                    row.push(err.clone());
+                }
+            }
+            return row;
+        })
+        .collect();
+    return runner::format_columns(&header, &rows);
+}
+
+/// Year, day, part and input name of a baseline entry.
+pub type BaselineKey = (i32, i32, i32, String);
+
+/// Benchmark results keyed by year, day, part and input name.
+///
+/// Stored as one whitespace-separated `<year> <day> <part> <input> <min> <median> <p95> <mean>`
+/// line per entry, with the durations in nanoseconds. Lines without the year, as written before
+/// there were other years, are read as entries of 2023.
+pub type Baseline = BTreeMap<BaselineKey, Stats>;
+
+pub fn parse_baseline(text: &str) -> Result<Baseline, String> {
+    let mut baseline = Baseline::new();
+    for (i, line) in text.lines().enumerate() {
+        let mut fields: Vec<&str> = line.split_whitespace().collect();
+        if fields.is_empty() {
+            continue;
+        }
+        let invalid = |_| format!("line {}: invalid number", i + 1);
+        let year = match fields.len() {
+            7 => DEFAULT_YEAR,
+            8 => fields.remove(0).parse::<i32>().map_err(invalid)?,
+            _ => return Err(format!("line {}: expected 8 fields", i + 1)),
+        };
+        let day = fields[0].parse::<i32>().map_err(invalid)?;
+        let part = fields[1].parse::<i32>().map_err(invalid)?;
+        let mut nanos = fields[3..]
+            .iter()
+            .map(|f| f.parse::<u64>().map_err(invalid));
+        let mut next = || nanos.next().unwrap().map(Duration::from_nanos);
+        let stats = Stats {
+            min: next()?,
+            median: next()?,
+            p95: next()?,
+            mean: next()?,
+        };
+        baseline.insert((year, day, part, fields[2].to_string()), stats);
+    }
+    return Ok(baseline);
+}
+
+pub fn format_baseline(baseline: &Baseline) -> String {
+    return baseline
+        .iter()
+        .map(|((year, day, part, input), stats)| {
+            format!(
+                "{} {} {} {} {}\n",
+                year,
+                day,
+                part,
+                input,
+                stats
+                    .values()
+                    .map(|(_, value)| value.as_nanos().to_string())
+                    .join(" ")
+            )
+        })
+        .collect();
+}
+
+pub fn read_baseline(path: &Path) -> Result<Baseline, String> {
+    return match std::fs::read_to_string(path) {
+        Ok(text) => parse_baseline(&text).map_err(|err| format!("{}: {}", path.display(), err)),
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(Baseline::new()),
+        Err(err) => Err(format!("{}: {}", path.display(), err)),
+    };
+}
+
+/// Adds or replaces one entry of the baseline file at `path`, keeping the others.
+pub fn save_baseline(path: &Path, key: BaselineKey, stats: Stats) -> Result<(), String> {
+    let mut baseline = read_baseline(path)?;
+    baseline.insert(key, stats);
+    return std::fs::write(path, format_baseline(&baseline))
+        .map_err(|err| format!("{}: {}", path.display(), err));
+}
+
+pub fn format_stats(stats: &Stats) -> String {
+    return stats
+        .values()
+        .map(|(name, value)| format!("{:<6} {}", name, format_duration(value)))
+        .join("\n");
+}
+
+pub fn format_comparison(stats: &Stats, baseline: &Stats) -> String {
+    return stats
+        .values()
+        .iter()
+        .zip(baseline.values().iter())
+        .map(|((name, value), (_, base))| {
+            let change = (value.as_secs_f64() / base.as_secs_f64() - 1.0) * 100.0;
+            format!(
+                "{:<6} {} (baseline {}, {:+.1}%)",
+                name,
+                format_duration(*value),
+                format_duration(*base),
+                change
+            )
+        })
+        .collect::<Vec<String>>()
+        .join("\n");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stats_from_samples() {
+        let samples: Vec<Duration> = (1..=20).rev().map(Duration::from_millis).collect();
+        let stats = Stats::from_samples(&samples);
+        assert_eq!(stats.min, Duration::from_millis(1));
+        assert_eq!(stats.median, Duration::from_millis(10));
+        assert_eq!(stats.p95, Duration::from_millis(19));
+        assert_eq!(stats.mean, Duration::from_micros(10_500));
+
+        let stats = Stats::from_samples(&[Duration::from_millis(3)]);
+        assert_eq!(stats.p95, Duration::from_millis(3));
+    }
+
+    #[test]
+    fn test_baseline_round_trip() {
+        let stats = Stats {
+            min: Duration::from_nanos(100),
+            median: Duration::from_nanos(150),
+            p95: Duration::from_nanos(300),
+            mean: Duration::from_nanos(170),
+        };
+        let baseline = Baseline::from([
+            ((2023, 16, 2, "real".to_string()), stats),
+            ((2024, 16, 2, "real".to_string()), stats),
+        ]);
+        let text = format_baseline(&baseline);
+        assert_eq!(
+            text,
+            "2023 16 2 real 100 150 300 170\n2024 16 2 real 100 150 300 170\n"
+        );
+        assert_eq!(parse_baseline(&text).unwrap(), baseline);
+        assert_eq!(
+            parse_baseline("16 2 real 100 150 300 170\n").unwrap(),
+            Baseline::from([((2023, 16, 2, "real".to_string()), stats)])
+        );
+
+        assert!(parse_baseline("16 2 real 100").is_err());
+    }
+    #[test]
+    fn test_format_bench_table() {
+        let stats = Stats::from_samples(&[Duration::from_micros(40), Duration::from_micros(60)]);
+        let runs = vec![
+            BenchRun {
+                year: 2023,
+                day: 6,
+                part: 1,
+                input: InputKind::Real,
+                result: Ok((Answer::from(288), stats)),
+            },
+            BenchRun {
+                year: 2023,
+                day: 6,
+                part: 2,
+                input: InputKind::Real,
+                result: Err("missing input".to_string()),
+            },
+        ];
+        assert_eq!(
+            format_bench_table(&runs),
+            [
+                "Day | Part | Input | Answer | Min  | Median | P95  | Mean | Status",
+                "----+------+-------+--------+------+--------+------+------+--------------",
+                "6   | 1    | real  | 288    | 40µs | 40µs   | 60µs | 50µs | ok",
+                "6   | 2    | real  |        |      |        |      |      | missing input",
+            ]
+            .join("\n")
+        );
+    }
+}
@@ -0,0 +1,362 @@
+use std::path::PathBuf;
+use std::time::Duration;
+
+use clap::{Args, Parser, Subcommand, ValueEnum};
+
+use advent_of_code_2023::input::{InputKind, InputSource};
+use advent_of_code_2023::registry::DEFAULT_YEAR;
+
+/// Advent of Code 2023 solutions.
+///
+/// Run without arguments to be prompted for the day, part and input interactively.
+#[derive(Debug, Parser)]
+#[command(
+    version,
+    about,
+    after_help = "Inputs are read from the `input` directory, or from the directory named by the \
+                  AOC_INPUT_DIR environment variable, in a subdirectory per year such as \
+                  `input/2023`. Inputs of 2023 are read from the directory itself if it has no \
+                  `2023` subdirectory."
+)]
+pub struct Cli {
+    /// Year of the puzzles
+    #[arg(
+        short,
+        long,
+        global = true,
+        default_value_t = DEFAULT_YEAR,
+        value_parser = clap::value_parser!(i32).range(2015..)
+    )]
+    pub year: i32,
+
+    #[command(subcommand)]
+    pub command: Option<Command>,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum Command {
+    /// Solve a single part of a single day
+    Solve(SolveArgs),
+    /// List the available puzzles
+    List,
+    /// Run both parts of every day on the real and test inputs and print a summary table
+    ///
+    /// Fails if a part panics, times out, or returns an error on a real input. Errors on example
+    /// inputs are shown but do not fail the run, since many examples only apply to one part.
+    RunAll(RunAllArgs),
+    /// Check answers against the ones stored next to each input, such as dayN.answers.txt
+    Verify(VerifyArgs),
+    /// Solve a single part of a single day again every time its input file changes
+    Watch(WatchArgs),
+    /// Show how a single part of a single day is solved, for the days that support it
+    Visualize(VisualizeArgs),
+    /// Time repeated runs of a single part of a single day
+    Bench(BenchArgs),
+    /// Time repeated runs of both parts of every day on the real inputs and print a summary table
+    BenchAll(BenchAllArgs),
+    /// Download the real inputs of some days into the input directory, skipping existing files
+    Fetch(FetchArgs),
+    /// Submit an answer and record the verdict in the submission history
+    Submit(SubmitArgs),
+    /// Create the module and empty inputs of a new day from the src/day0.rs template
+    New(NewArgs),
+    /// Prompt for the day, part and input on stdin
+    Interactive,
+}
+
+/// Selects a day, a part and the input to solve it on.
+#[derive(Debug, Args)]
+pub struct PuzzleArgs {
+    /// Day number
+    #[arg(value_parser = clap::value_parser!(i32).range(1..=25))]
+    pub day: i32,
+
+    /// Part number
+    #[arg(value_parser = clap::value_parser!(i32).range(1..=2))]
+    pub part: i32,
+
+    /// Use an example input (dayNtest.txt, or dayNtestN.txt for N > 1) instead of the real one
+    #[arg(
+        short,
+        long,
+        value_name = "N",
+        num_args = 0..=1,
+        default_missing_value = "1",
+        value_parser = clap::value_parser!(u32).range(1..),
+        conflicts_with = "input"
+    )]
+    pub test: Option<u32>,
+
+    /// Read the puzzle input from this file instead of the input directory, or from stdin if `-`
+    #[arg(short, long, value_name = "PATH")]
+    pub input: Option<PathBuf>,
+}
+impl PuzzleArgs {
+    pub fn input_kind(&self) -> InputKind {
+        return match self.test {
+            Some(n) => InputKind::Test(n),
+            None => InputKind::Real,
+        };
+    }
+
+    pub fn input_source(&self, year: i32) -> InputSource {
+        return match &self.input {
+            Some(path) => InputSource::from_path(path),
+            None => InputSource::for_day(year, self.day, self.input_kind()),
+        };
+    }
+
+    /// Short name for the input: `real`, `test`, `testN` or the source given with `--input`.
+    pub fn input_name(&self) -> String {
+        return match &self.input {
+            Some(path) => InputSource::from_path(path).to_string(),
+            None => self.input_kind().to_string(),
+        };
+    }
+}
+
+#[derive(Debug, Args)]
+pub struct SolveArgs {
+    #[command(flatten)]
+    pub puzzle: PuzzleArgs,
+
+    /// How to print the answer
+    #[arg(short, long, value_enum, default_value_t = OutputFormat::Plain)]
+    pub format: OutputFormat,
+
+    #[command(flatten)]
+    pub report: ReportArgs,
+}
+
+#[derive(Debug, Args)]
+pub struct WatchArgs {
+    #[command(flatten)]
+    pub puzzle: PuzzleArgs,
+
+    /// Check the input file for changes this often
+    #[arg(long, value_name = "SECS", default_value = "0.5", value_parser = parse_interval)]
+    pub interval: Duration,
+
+    /// Give up on a run after this many seconds (0 to wait forever)
+    #[arg(long, value_name = "SECS", default_value = "60", value_parser = parse_seconds)]
+    pub timeout: Duration,
+}
+impl WatchArgs {
+    pub fn timeout(&self) -> Option<Duration> {
+        return time_limit(self.timeout);
+    }
+}
+
+#[derive(Debug, Args)]
+pub struct VisualizeArgs {
+    #[command(flatten)]
+    pub puzzle: PuzzleArgs,
+
+    /// Wait for enter between frames instead of animating them
+    #[arg(long)]
+    pub step: bool,
+
+    /// Time between frames of the animation
+    #[arg(long, value_name = "MS", default_value_t = 100)]
+    pub delay: u64,
+
+    /// Draw the frames without colors
+    #[arg(long)]
+    pub no_color: bool,
+}
+
+#[derive(Debug, Args)]
+pub struct BenchArgs {
+    #[command(flatten)]
+    pub puzzle: PuzzleArgs,
+
+    /// Number of measured runs
+    #[arg(short = 'n', long, default_value_t = 10, value_parser = clap::value_parser!(u64).range(1..))]
+    pub iterations: u64,
+
+    /// Number of unmeasured runs before measuring
+    #[arg(short, long, default_value_t = 1)]
+    pub warmup: u64,
+
+    /// Compare the results against the entry for this year, day, part and input in a baseline file
+    #[arg(short, long, value_name = "PATH")]
+    pub compare: Option<PathBuf>,
+
+    /// Save the results as the entry for this year, day, part and input in a baseline file
+    #[arg(short, long, value_name = "PATH")]
+    pub save: Option<PathBuf>,
+
+    #[command(flatten)]
+    pub report: ReportArgs,
+}
+
+/// Options for running many parts in one go.
+#[derive(Debug, Args)]
+pub struct RunArgs {
+    /// Give up on a part after this many seconds (0 to wait forever)
+    #[arg(long, value_name = "SECS", default_value = "60", value_parser = parse_seconds)]
+    pub timeout: Duration,
+
+    /// Run this many parts at once (0 for one per CPU)
+    #[arg(short, long, value_name = "N", default_value_t = 1)]
+    pub jobs: usize,
+}
+impl RunArgs {
+    pub fn timeout(&self) -> Option<Duration> {
+        return time_limit(self.timeout);
+    }
+}
+
+/// Parses a number of seconds, such as `0.5`, rejecting negative, infinite and huge values.
+fn parse_seconds(text: &str) -> Result<Duration, String> {
+    let seconds: f64 = text.parse().map_err(|err| format!("{}", err))?;
+    return Duration::try_from_secs_f64(seconds)
+        .map_err(|_| "expected a finite, non-negative number of seconds".to_string());
+}
+
+/// Parses a number of seconds that must be more than zero.
+fn parse_interval(text: &str) -> Result<Duration, String> {
+    let interval = parse_seconds(text)?;
+    if interval.is_zero() {
+        return Err("the interval must be positive".to_string());
+    }
+    return Ok(interval);
+}
+
+/// A timeout of zero means waiting forever.
+fn time_limit(timeout: Duration) -> Option<Duration> {
+    return match timeout.is_zero() {
+        true => None,
+        false => Some(timeout),
+    };
+}
+
+#[derive(Debug, Args)]
+pub struct RunAllArgs {
+    #[command(flatten)]
+    pub run: RunArgs,
+
+    #[command(flatten)]
+    pub report: ReportArgs,
+}
+
+/// Options for writing a machine-readable report of the parts that ran.
+#[derive(Debug, Args)]
+pub struct ReportArgs {
+    /// Print a report in this format instead of the usual output
+    #[arg(long, value_name = "FORMAT", value_enum)]
+    pub report: Option<ReportFormat>,
+
+    /// Write the report to this file and keep the usual output
+    #[arg(long, value_name = "PATH", requires = "report")]
+    pub report_file: Option<PathBuf>,
+}
+impl ReportArgs {
+    /// Whether the report is printed in place of the usual output.
+    pub fn replaces_output(&self) -> bool {
+        return self.report.is_some() && self.report_file.is_none();
+    }
+}
+
+#[derive(Debug, Args)]
+pub struct BenchAllArgs {
+    /// Number of measured runs of each part
+    #[arg(short = 'n', long, default_value_t = 10, value_parser = clap::value_parser!(u64).range(1..))]
+    pub iterations: u64,
+
+    /// Number of unmeasured runs of each part before measuring
+    #[arg(short, long, default_value_t = 1)]
+    pub warmup: u64,
+
+    /// Benchmark this many parts at once (0 for one per CPU). Timings from concurrent runs are
+    /// only comparable with runs using the same number of jobs
+    #[arg(short, long, value_name = "N", default_value_t = 1)]
+    pub jobs: usize,
+
+    /// Save the results of every part that ran to a baseline file
+    #[arg(short, long, value_name = "PATH")]
+    pub save: Option<PathBuf>,
+
+    #[command(flatten)]
+    pub report: ReportArgs,
+}
+
+#[derive(Debug, Args)]
+pub struct VerifyArgs {
+    /// Only verify these days (all days if omitted)
+    #[arg(value_parser = clap::value_parser!(i32).range(1..=25))]
+    pub days: Vec<i32>,
+
+    #[command(flatten)]
+    pub run: RunArgs,
+
+    /// Store the answers of parts that have no expected answer yet
+    #[arg(long)]
+    pub record: bool,
+}
+
+/// Where to reach an Advent of Code compatible server.
+#[derive(Debug, Args)]
+pub struct ServerArgs {
+    /// Base URL of the server (defaults to $AOC_BASE_URL, then https://adventofcode.com)
+    #[arg(long, value_name = "URL")]
+    pub base_url: Option<String>,
+}
+
+#[derive(Debug, Args)]
+pub struct FetchArgs {
+    /// Days to download
+    #[arg(required = true, value_parser = clap::value_parser!(i32).range(1..=25))]
+    pub days: Vec<i32>,
+
+    #[command(flatten)]
+    pub server: ServerArgs,
+}
+
+#[derive(Debug, Args)]
+pub struct SubmitArgs {
+    /// Day number
+    #[arg(value_parser = clap::value_parser!(i32).range(1..=25))]
+    pub day: i32,
+
+    /// Part number
+    #[arg(value_parser = clap::value_parser!(i32).range(1..=2))]
+    pub part: i32,
+
+    /// Answer to submit (solves the real input if omitted)
+    pub answer: Option<String>,
+
+    #[command(flatten)]
+    pub server: ServerArgs,
+}
+
+#[derive(Debug, Args)]
+pub struct NewArgs {
+    /// Day number
+    #[arg(value_parser = clap::value_parser!(i32).range(1..=25))]
+    pub day: i32,
+
+    /// Puzzle title shown by `list`
+    #[arg(long, default_value = "")]
+    pub title: String,
+
+    /// Source directory of the crate
+    #[arg(long, value_name = "DIR", default_value = "src")]
+    pub src: PathBuf,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum OutputFormat {
+    /// Only the answer
+    Plain,
+    /// The answer along with the day, part and input it was computed from
+    Verbose,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ReportFormat {
+    /// An array with one object per part
+    Json,
+    /// A header line and one line per part
+    Csv,
+}
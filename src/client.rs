@@ -0,0 +1,192 @@
+use std::time::Duration;
+
+/// Server used unless [`BASE_URL_ENV`] or an explicit base URL says otherwise.
+pub const DEFAULT_BASE_URL: &str = "https://adventofcode.com";
+
+/// Environment variable that overrides [`DEFAULT_BASE_URL`].
+pub const BASE_URL_ENV: &str = "AOC_BASE_URL";
+
+/// Environment variable holding the value of the `session` cookie of a logged-in browser.
+pub const SESSION_ENV: &str = "AOC_SESSION";
+
+/// File the session token is read from when [`SESSION_ENV`] is not set.
+pub const SESSION_FILE: &str = ".aoc-session";
+
+const USER_AGENT: &str = concat!(
+    "advent-of-code-2023/",
+    env!("CARGO_PKG_VERSION"),
+    " (github.com/j1h7e7/advent-of-code-2023)"
+);
+
+/// A client for an Advent of Code compatible server.
+pub struct Client {
+    base_url: String,
+    session: Option<String>,
+    agent: ureq::Agent,
+}
+impl Client {
+    pub fn new(base_url: &str, session: Option<String>) -> Client {
+        return Client {
+            base_url: base_url.trim_end_matches('/').to_string(),
+            session,
+            agent: ureq::AgentBuilder::new()
+                .user_agent(USER_AGENT)
+                .timeout(Duration::from_secs(30))
+                .build(),
+        };
+    }
+
+    /// A client configured from the environment. `base_url` takes precedence over
+    /// [`BASE_URL_ENV`], and the session token comes from [`SESSION_ENV`] or [`SESSION_FILE`].
+    pub fn from_env(base_url: Option<&str>) -> Result<Client, String> {
+        let base_url = match base_url {
+            Some(url) => url.to_string(),
+            None => std::env::var(BASE_URL_ENV).unwrap_or(DEFAULT_BASE_URL.to_string()),
+        };
+        let session = match std::env::var(SESSION_ENV) {
+            Ok(session) => Some(session),
+            Err(_) => match std::fs::read_to_string(SESSION_FILE) {
+                Ok(session) => Some(session),
+                Err(err) if err.kind() == std::io::ErrorKind::NotFound => None,
+                Err(err) => return Err(format!("{}: {}", SESSION_FILE, err)),
+            },
+        };
+        let session = session
+            .map(|session| session.trim().to_string())
+            .filter(|session| !session.is_empty());
+        return Ok(Client::new(&base_url, session));
+    }
+
+    pub fn base_url(&self) -> &str {
+        return &self.base_url;
+    }
+
+    pub fn day_url(&self, year: i32, day: i32) -> String {
+        return format!("{}/{}/day/{}", self.base_url, year, day);
+    }
+
+    fn cookie(&self) -> Result<String, String> {
+        return match &self.session {
+            Some(session) => Ok(format!("session={}", session)),
+            None => Err(format!(
+                "no session token: set {} or write it to {}",
+                SESSION_ENV, SESSION_FILE
+            )),
+        };
+    }
+
+    pub fn get(&self, url: &str) -> Result<String, String> {
+        let response = self.agent.get(url).set("Cookie", &self.cookie()?).call();
+        return read_response(url, response);
+    }
+
+    pub fn post_form(&self, url: &str, fields: &[(&str, &str)]) -> Result<String, String> {
+        let response = self
+            .agent
+            .post(url)
+            .set("Cookie", &self.cookie()?)
+            .send_form(fields);
+        return read_response(url, response);
+    }
+}
+
+fn read_response(
+    url: &str,
+    response: Result<ureq::Response, ureq::Error>,
+) -> Result<String, String> {
+    return match response {
+        Ok(response) => response
+            .into_string()
+            .map_err(|err| format!("{}: {}", url, err)),
+        Err(ureq::Error::Status(code, response)) => Err(format!(
+            "{}: server returned {} {}",
+            url,
+            code,
+            response.status_text()
+        )),
+        Err(err) => Err(format!("{}: {}", url, err)),
+    };
+}
+
+/// A server on localhost that answers each connection with the next of `responses` and returns
+/// the requests it received once all responses have been sent.
+#[cfg(test)]
+pub(crate) fn mock_server(
+    responses: Vec<(u16, &'static str)>,
+) -> (String, std::thread::JoinHandle<Vec<String>>) {
+    use std::io::{BufRead, BufReader, Read, Write};
+
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+    let handle = std::thread::spawn(move || {
+        let mut requests: Vec<String> = Vec::new();
+        for (status, body) in responses {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut reader = BufReader::new(stream.try_clone().unwrap());
+            let mut request = String::new();
+            let mut content_length = 0;
+            loop {
+                let mut line = String::new();
+                reader.read_line(&mut line).unwrap();
+                if let Some(length) = line.to_lowercase().strip_prefix("content-length:") {
+                    content_length = length.trim().parse::<usize>().unwrap();
+                }
+                request.push_str(&line);
+                if line == "\r\n" || line.is_empty() {
+                    break;
+                }
+            }
+            let mut content = vec![0; content_length];
+            reader.read_exact(&mut content).unwrap();
+            request.push_str(&String::from_utf8(content).unwrap());
+            requests.push(request);
+
+            write!(
+                stream,
+                "HTTP/1.1 {} Mock\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                status,
+                body.len(),
+                body
+            )
+            .unwrap();
+        }
+        return requests;
+    });
+    return (url, handle);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_get() {
+        let (url, server) = mock_server(vec![(200, "hello"), (404, "gone")]);
+        let client = Client::new(&format!("{}/", url), Some("abc".to_string()));
+        assert_eq!(client.base_url(), url);
+        assert_eq!(
+            client.get(&client.day_url(2023, 1)),
+            Ok("hello".to_string())
+        );
+
+        let err = client.get(&client.day_url(2023, 2)).unwrap_err();
+        assert!(
+            err.ends_with("/2023/day/2: server returned 404 Mock"),
+            "{}",
+            err
+        );
+
+        let requests = server.join().unwrap();
+        assert!(requests[0].starts_with("GET /2023/day/1 HTTP/1.1\r\n"));
+        assert!(requests[0].contains("Cookie: session=abc\r\n"));
+    }
+
+    #[test]
+    fn test_missing_session() {
+        let client = Client::new("http://127.0.0.1:1", None);
+        assert!(client
+            .get(&client.day_url(2023, 1))
+            .unwrap_err()
+            .starts_with("no session token"));
+    }
+}
@@ -1,13 +1,21 @@
+use crate::solve::{Answer, PuzzleError};
+
 pub struct Day0Puzzle {}
-impl super::solve::Puzzle<String> for Day0Puzzle {
-    fn solve(&self, document: &str) -> String {
+impl super::solve::Puzzle for Day0Puzzle {
+    fn solve(&self, document: &str) -> Result<Answer, PuzzleError> {
         panic!("Not implemented");
     }
 
-    fn solve2(&self, document: &str) -> String {
+    fn solve2(&self, document: &str) -> Result<Answer, PuzzleError> {
         panic!("Not implemented");
     }
 }
+crate::register_puzzle!(
+    day: 0,
+    title: "",
+    tags: [],
+    puzzle: Day0Puzzle {},
+);
 
 #[cfg(test)]
 mod tests {
@@ -1,6 +1,8 @@
 use phf::phf_map;
 
-fn extract_number_from_line(line: &str) -> i32 {
+use crate::solve::{Answer, PuzzleError};
+
+pub fn extract_number_from_line(line: &str) -> Option<i32> {
     let mut l: i32 = -1i32;
     let mut r: i32 = 0i32;
     for c in line.chars() {
@@ -13,21 +15,25 @@ fn extract_number_from_line(line: &str) -> i32 {
         }
         r = v;
     }
+    if l == -1i32 {
+        return None;
+    }
 
-    return l * 10 + r;
+    return Some(l * 10 + r);
 }
 
-fn get_calibration_sum(document: &str) -> i32 {
+pub fn get_calibration_sum(document: &str) -> Result<i32, PuzzleError> {
     let mut sum: i32 = 0i32;
     for line in document.lines() {
-        let number: i32 = extract_number_from_line(line);
+        let number: i32 = extract_number_from_line(line)
+            .ok_or_else(|| PuzzleError::parse(document, line, "no digit in line"))?;
         sum += number;
     }
 
-    return sum;
+    return Ok(sum);
 }
 
-static NUMBERS: phf::Map<&'static str, i32> = phf_map! {
+pub static NUMBERS: phf::Map<&'static str, i32> = phf_map! {
     "one" => 1,
     "two" => 2,
     "three" => 3,
@@ -39,7 +45,7 @@ static NUMBERS: phf::Map<&'static str, i32> = phf_map! {
     "nine" => 9,
 };
 
-fn extract_number_from_line_include_text(line: &str) -> i32 {
+pub fn extract_number_from_line_include_text(line: &str) -> Option<i32> {
     let mut numbers: Vec<i32> = Vec::new();
     let mut prefix: String = String::new();
 
@@ -56,31 +62,40 @@ fn extract_number_from_line_include_text(line: &str) -> i32 {
             }
         }
     }
-    assert!(numbers.len() > 0);
+    if numbers.len() == 0 {
+        return None;
+    }
 
-    return numbers[0] * 10 + numbers[numbers.len() - 1];
+    return Some(numbers[0] * 10 + numbers[numbers.len() - 1]);
 }
 
-fn get_updated_calibration_sum(document: &str) -> i32 {
+pub fn get_updated_calibration_sum(document: &str) -> Result<i32, PuzzleError> {
     let mut sum: i32 = 0i32;
     for line in document.lines() {
-        let number: i32 = extract_number_from_line_include_text(line);
+        let number: i32 = extract_number_from_line_include_text(line)
+            .ok_or_else(|| PuzzleError::parse(document, line, "no digit or digit name in line"))?;
         sum += number;
     }
 
-    return sum;
+    return Ok(sum);
 }
 
 pub struct Day1Puzzle {}
-impl super::solve::Puzzle<String> for Day1Puzzle {
-    fn solve(&self, document: &str) -> String {
-        return get_calibration_sum(document).to_string();
+impl super::solve::Puzzle for Day1Puzzle {
+    fn solve(&self, document: &str) -> Result<Answer, PuzzleError> {
+        return Ok(get_calibration_sum(document)?.into());
     }
 
-    fn solve2(&self, document: &str) -> String {
-        return get_updated_calibration_sum(document).to_string();
+    fn solve2(&self, document: &str) -> Result<Answer, PuzzleError> {
+        return Ok(get_updated_calibration_sum(document)?.into());
     }
 }
+crate::register_puzzle!(
+    day: 1,
+    title: "Trebuchet?!",
+    tags: ["parsing", "strings"],
+    puzzle: Day1Puzzle {},
+);
 
 #[cfg(test)]
 mod tests {
@@ -88,20 +103,30 @@ mod tests {
 
     #[test]
     fn test_extract_number_from_line() {
-        assert_eq!(extract_number_from_line("1abc2"), 12);
-        assert_eq!(extract_number_from_line("h5ellowor6ld"), 56);
-        assert_eq!(extract_number_from_line("te7st"), 77);
+        assert_eq!(extract_number_from_line("1abc2"), Some(12));
+        assert_eq!(extract_number_from_line("h5ellowor6ld"), Some(56));
+        assert_eq!(extract_number_from_line("te7st"), Some(77));
+        assert_eq!(extract_number_from_line("test"), None);
     }
 
     #[test]
     fn test_extract_number_from_line_include_text() {
-        assert_eq!(extract_number_from_line_include_text("one3two"), 12);
-        assert_eq!(extract_number_from_line_include_text("three4threeight"), 38);
+        assert_eq!(extract_number_from_line_include_text("one3two"), Some(12));
+        assert_eq!(
+            extract_number_from_line_include_text("three4threeight"),
+            Some(38)
+        );
     }
 
     #[test]
     fn test_get_calibration_sum() {
         let document: &str = "1abc2\nh5ellowor6ld\nte7st";
-        assert_eq!(get_calibration_sum(document), 145);
+        assert_eq!(get_calibration_sum(document), Ok(145));
+
+        let document: &str = "1abc2\ntest";
+        assert_eq!(
+            get_calibration_sum(document).unwrap_err().to_string(),
+            "parse error at line 2, column 1: no digit in line"
+        );
     }
 }
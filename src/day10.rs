@@ -2,7 +2,9 @@ use std::collections::{HashMap, HashSet};
 
 use phf::phf_map;
 
-static PIPES: phf::Map<&'static str, ((i32, i32), (i32, i32))> = phf_map! {
+use crate::solve::{Answer, PuzzleError};
+
+pub static PIPES: phf::Map<&'static str, ((i32, i32), (i32, i32))> = phf_map! {
     "-" => ((-1,0),(1,0)),
     "|" => ((0,-1),(0,1)),
     "L" => ((0,-1),(1,0)),
@@ -11,11 +13,13 @@ static PIPES: phf::Map<&'static str, ((i32, i32), (i32, i32))> = phf_map! {
     "F" => ((0,1),(1,0)),
 };
 
-fn get_nodes(document: &str) -> (HashMap<(i32, i32), ((i32, i32), (i32, i32))>, (i32, i32)) {
+pub fn get_nodes(
+    document: &str,
+) -> Result<(HashMap<(i32, i32), ((i32, i32), (i32, i32))>, (i32, i32)), PuzzleError> {
     let mut s_cell: (i32, i32) = (-1, -1);
     let mut nodes: HashMap<(i32, i32), ((i32, i32), (i32, i32))> = HashMap::new();
     for (y, line) in document.lines().enumerate() {
-        for (x, c) in line.chars().enumerate() {
+        for (x, (i, c)) in line.char_indices().enumerate() {
             if c == '.' {
                 continue;
             }
@@ -23,7 +27,13 @@ fn get_nodes(document: &str) -> (HashMap<(i32, i32), ((i32, i32), (i32, i32))>,
                 s_cell = (x as i32, y as i32);
                 continue;
             }
-            let pipe = PIPES[&c.to_string()];
+            let Some(&pipe) = PIPES.get(&c.to_string()) else {
+                return Err(PuzzleError::parse(
+                    document,
+                    &line[i..],
+                    format!("invalid pipe `{}`", c),
+                ));
+            };
             nodes.insert(
                 (x as i32, y as i32),
                 (
@@ -34,18 +44,45 @@ fn get_nodes(document: &str) -> (HashMap<(i32, i32), ((i32, i32), (i32, i32))>,
         }
     }
 
+    if s_cell == (-1, -1) {
+        return Err(PuzzleError::unsolvable("no starting tile `S`"));
+    }
+
     let mut s_conns: Vec<(i32, i32)> = Vec::new();
     for (src, (d1, d2)) in nodes.iter() {
         if *d1 == s_cell || *d2 == s_cell {
             s_conns.push(*src);
         }
     }
+    if s_conns.len() != 2 {
+        return Err(PuzzleError::unsolvable(format!(
+            "starting tile is connected to {} pipes instead of 2",
+            s_conns.len()
+        )));
+    }
     nodes.insert(s_cell, (s_conns[0], s_conns[1]));
 
-    return (nodes, s_cell);
+    // Follow the pipes from the starting tile; each pipe must lead on to one that connects back,
+    // until the loop returns to the start.
+    let (mut previous, mut current) = (s_cell, s_conns[0]);
+    for _ in 0..nodes.len() {
+        if current == s_cell {
+            return Ok((nodes, s_cell));
+        }
+        let Some(&(d1, d2)) = nodes.get(&current) else {
+            break;
+        };
+        let next = match (d1 == previous, d2 == previous) {
+            (true, _) => d2,
+            (_, true) => d1,
+            _ => break,
+        };
+        (previous, current) = (current, next);
+    }
+    return Err(PuzzleError::unsolvable("pipe loop is not closed"));
 }
 
-fn get_farthest_distance(
+pub fn get_farthest_distance(
     nodes: &HashMap<(i32, i32), ((i32, i32), (i32, i32))>,
     start: (i32, i32),
 ) -> i32 {
@@ -74,7 +111,7 @@ fn get_farthest_distance(
     return distance;
 }
 
-fn get_main_path(
+pub fn get_main_path(
     nodes: &HashMap<(i32, i32), ((i32, i32), (i32, i32))>,
     start: (i32, i32),
 ) -> HashSet<(i32, i32)> {
@@ -101,7 +138,10 @@ fn get_main_path(
     return visited;
 }
 
-fn get_s_type(s_node: (i32, i32), nodes: &HashMap<(i32, i32), ((i32, i32), (i32, i32))>) -> char {
+pub fn get_s_type(
+    s_node: (i32, i32),
+    nodes: &HashMap<(i32, i32), ((i32, i32), (i32, i32))>,
+) -> char {
     let (n1, n2) = nodes[&s_node];
     let d1 = (n1.0 - s_node.0, n1.1 - s_node.1);
     let d2 = (n2.0 - s_node.0, n2.1 - s_node.1);
@@ -115,8 +155,8 @@ fn get_s_type(s_node: (i32, i32), nodes: &HashMap<(i32, i32), ((i32, i32), (i32,
     return ' ';
 }
 
-fn get_internal_points(document: &str) -> HashSet<(i32, i32)> {
-    let (nodes, s_cell) = get_nodes(document);
+pub fn get_internal_points(document: &str) -> Result<HashSet<(i32, i32)>, PuzzleError> {
+    let (nodes, s_cell) = get_nodes(document)?;
     let main_path = get_main_path(&nodes, s_cell);
     let mut internal_points: HashSet<(i32, i32)> = HashSet::new();
 
@@ -150,20 +190,26 @@ fn get_internal_points(document: &str) -> HashSet<(i32, i32)> {
             }
         }
     }
-    return internal_points;
+    return Ok(internal_points);
 }
 
 pub struct Day10Puzzle {}
-impl super::solve::Puzzle<String> for Day10Puzzle {
-    fn solve(&self, document: &str) -> String {
-        let (nodes, s_cell) = get_nodes(document);
-        return get_farthest_distance(&nodes, s_cell).to_string();
+impl super::solve::Puzzle for Day10Puzzle {
+    fn solve(&self, document: &str) -> Result<Answer, PuzzleError> {
+        let (nodes, s_cell) = get_nodes(document)?;
+        return Ok(get_farthest_distance(&nodes, s_cell).into());
     }
 
-    fn solve2(&self, document: &str) -> String {
-        return get_internal_points(document).len().to_string();
+    fn solve2(&self, document: &str) -> Result<Answer, PuzzleError> {
+        return Ok(get_internal_points(document)?.len().into());
     }
 }
+crate::register_puzzle!(
+    day: 10,
+    title: "Pipe Maze",
+    tags: ["grid", "graph"],
+    puzzle: Day10Puzzle {},
+);
 
 #[cfg(test)]
 mod tests {
@@ -173,14 +219,27 @@ mod tests {
 
     #[test]
     fn test_get_nodes() {
-        let (nodes, _) = get_nodes(DOCUMENT);
+        let (nodes, _) = get_nodes(DOCUMENT).unwrap();
         assert_eq!(nodes.len(), 8);
         assert!(nodes[&(0, 0)] == ((0, 1), (1, 0)) || nodes[&(0, 0)] == ((1, 0), (0, 1)));
+
+        assert_eq!(
+            get_nodes("S-7\n|x|\nL-J").unwrap_err().to_string(),
+            "parse error at line 2, column 2: invalid pipe `x`"
+        );
+        assert!(get_nodes("S-7\n..|\nL-J").is_err());
+        assert_eq!(
+            get_nodes("..F7.\n.F|.\nSJ.L7\n|F--J\nLJ...")
+                .unwrap_err()
+                .to_string(),
+            "unsolvable input: pipe loop is not closed"
+        );
+        assert!(get_nodes("..F7.\n.F-J|.\nSJ.L7\n|F--J\nLJ...").is_err());
     }
 
     #[test]
     fn test_get_main_path() {
-        let (nodes, s_cell) = get_nodes(DOCUMENT);
+        let (nodes, s_cell) = get_nodes(DOCUMENT).unwrap();
         let main_path = get_main_path(&nodes, s_cell);
         assert_eq!(
             main_path,
@@ -199,13 +258,13 @@ mod tests {
 
     #[test]
     fn test_get_internal_points() {
-        let internal_points = get_internal_points(DOCUMENT);
+        let internal_points = get_internal_points(DOCUMENT).unwrap();
         assert_eq!(internal_points, HashSet::from_iter(vec![(1, 1)]));
     }
 
     #[test]
     fn test_get_s_type() {
-        let (nodes, _) = get_nodes(DOCUMENT);
+        let (nodes, _) = get_nodes(DOCUMENT).unwrap();
         assert_eq!(get_s_type((0, 0), &nodes), 'F');
         assert_eq!(get_s_type((0, 2), &nodes), 'L');
         assert_eq!(get_s_type((2, 0), &nodes), '7');
@@ -1,4 +1,6 @@
-fn sum_abs_diff(arr: Vec<i64>) -> i64 {
+use crate::solve::{check_chars, grid_size, Answer, PuzzleError};
+
+pub fn sum_abs_diff(arr: Vec<i64>) -> i64 {
     let mut a = arr.clone();
     let n = a.len() as i64;
     a.sort_unstable();
@@ -9,7 +11,7 @@ fn sum_abs_diff(arr: Vec<i64>) -> i64 {
         .sum();
 }
 
-fn get_galaxy_positions(document: &str, expansion_factor: i64) -> Vec<(i64, i64)> {
+pub fn get_galaxy_positions(document: &str, expansion_factor: i64) -> Vec<(i64, i64)> {
     let mut positions = Vec::new();
     let mut rows: Vec<i64> = document.lines().map(|_| expansion_factor).collect();
     let mut cols: Vec<i64> = document
@@ -52,21 +54,26 @@ fn get_galaxy_positions(document: &str, expansion_factor: i64) -> Vec<(i64, i64)
 }
 
 pub struct Day11Puzzle {}
-impl super::solve::Puzzle<String> for Day11Puzzle {
-    fn solve(&self, document: &str) -> String {
+impl super::solve::Puzzle for Day11Puzzle {
+    fn solve(&self, document: &str) -> Result<Answer, PuzzleError> {
+        grid_size(document)?;
+        check_chars(document, ".#")?;
         let positions = get_galaxy_positions(document, 1);
         let x: Vec<i64> = positions.iter().map(|&(i, _)| i).collect();
         let y: Vec<i64> = positions.iter().map(|&(_, j)| j).collect();
-        return (sum_abs_diff(x) + sum_abs_diff(y)).to_string();
+        return Ok((sum_abs_diff(x) + sum_abs_diff(y)).into());
     }
 
-    fn solve2(&self, document: &str) -> String {
+    fn solve2(&self, document: &str) -> Result<Answer, PuzzleError> {
+        grid_size(document)?;
+        check_chars(document, ".#")?;
         let positions = get_galaxy_positions(document, 999999);
         let x: Vec<i64> = positions.iter().map(|&(i, _)| i).collect();
         let y: Vec<i64> = positions.iter().map(|&(_, j)| j).collect();
-        return (sum_abs_diff(x) + sum_abs_diff(y)).to_string();
+        return Ok((sum_abs_diff(x) + sum_abs_diff(y)).into());
     }
 }
+crate::register_puzzle!(day: 11, title: "Cosmic Expansion", tags: ["grid"], puzzle: Day11Puzzle {});
 
 #[cfg(test)]
 mod tests {
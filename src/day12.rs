@@ -1,18 +1,26 @@
 use cached::proc_macro::cached;
 use cached::UnboundCache;
 
-fn get_pattern_and_blobs(line: &str) -> (&str, Vec<i64>) {
-    let pattern = line.split(' ').nth(0).unwrap();
-    let blobs = line.split(' ').nth(1).unwrap();
+use crate::solve::{check_chars, parse_token, Answer, PuzzleError};
+
+pub fn get_pattern_and_blobs(line: &str) -> Result<(&str, Vec<i64>), PuzzleError> {
+    let Some((pattern, blobs)) = line.split_once(' ') else {
+        return Err(PuzzleError::missing(
+            line,
+            line,
+            "expected a pattern and group sizes",
+        ));
+    };
+    check_chars(pattern, ".#?")?;
     // let re = Regex::new(r"\.+").unwrap();
-    return (
+    return Ok((
         // re.replace_all(pattern, ".").clone(),
         pattern,
         blobs
             .split(',')
-            .map(|x| x.parse::<i64>().unwrap())
-            .collect(),
-    );
+            .map(|x| parse_token::<i64>(line, x))
+            .collect::<Result<Vec<i64>, PuzzleError>>()?,
+    ));
 }
 
 #[cached(
@@ -20,7 +28,7 @@ fn get_pattern_and_blobs(line: &str) -> (&str, Vec<i64>) {
     create = "{ UnboundCache::new() }",
     convert = r#"{ format!("{} {:?}", pattern, blobs) }"#
 )]
-fn get_possibilities(pattern: &str, blobs: &Vec<i64>) -> i64 {
+pub fn get_possibilities(pattern: &str, blobs: &Vec<i64>) -> i64 {
     if pattern.len() == 0 && blobs.len() == 0 {
         return 1;
     } else if pattern.len() == 0 {
@@ -74,29 +82,37 @@ fn get_possibilities(pattern: &str, blobs: &Vec<i64>) -> i64 {
 }
 
 pub struct Day12Puzzle {}
-impl super::solve::Puzzle<String> for Day12Puzzle {
-    fn solve(&self, document: &str) -> String {
+impl super::solve::Puzzle for Day12Puzzle {
+    fn solve(&self, document: &str) -> Result<Answer, PuzzleError> {
         let lines = document.lines();
         let mut ans = 0_i64;
         for line in lines {
-            let (pattern, blobs) = get_pattern_and_blobs(line);
+            let (pattern, blobs) =
+                get_pattern_and_blobs(line).map_err(|err| err.within(document, line))?;
             ans += get_possibilities(pattern, &blobs);
         }
 
-        return ans.to_string();
+        return Ok(ans.into());
     }
 
-    fn solve2(&self, document: &str) -> String {
+    fn solve2(&self, document: &str) -> Result<Answer, PuzzleError> {
         let lines = document.lines();
         let mut ans = 0_i64;
         for line in lines {
-            let (pattern, blobs) = get_pattern_and_blobs(line);
+            let (pattern, blobs) =
+                get_pattern_and_blobs(line).map_err(|err| err.within(document, line))?;
             ans += get_possibilities(&vec![pattern].repeat(5).join("?"), &blobs.repeat(5));
         }
 
-        return ans.to_string();
+        return Ok(ans.into());
     }
 }
+crate::register_puzzle!(
+    day: 12,
+    title: "Hot Springs",
+    tags: ["dynamic-programming"],
+    puzzle: Day12Puzzle {},
+);
 
 #[cfg(test)]
 mod tests {
@@ -106,7 +122,13 @@ mod tests {
     fn test_get_pattern_and_blobs() {
         assert_eq!(
             get_pattern_and_blobs("#..## 1,3,3"),
-            ("#..##", vec![1, 3, 3])
+            Ok(("#..##", vec![1, 3, 3]))
+        );
+        assert_eq!(
+            get_pattern_and_blobs("#..## 1,a,3")
+                .unwrap_err()
+                .to_string(),
+            "parse error at line 1, column 9: invalid value `a`: invalid digit found in string"
         );
     }
 
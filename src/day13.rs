@@ -1,4 +1,6 @@
-fn get_row_pattern(terrain: &str) -> Vec<u128> {
+use crate::solve::{check_chars, grid_size, Answer, PuzzleError};
+
+pub fn get_row_pattern(terrain: &str) -> Vec<u128> {
     return terrain
         .lines()
         .map(|line| {
@@ -14,7 +16,7 @@ fn get_row_pattern(terrain: &str) -> Vec<u128> {
         .collect();
 }
 
-fn get_col_pattern(terrain: &str) -> Vec<u128> {
+pub fn get_col_pattern(terrain: &str) -> Vec<u128> {
     let mut ans = vec![0_u128; terrain.lines().nth(0).unwrap().len()];
     for line in terrain.lines() {
         for (i, c) in line.chars().enumerate() {
@@ -27,7 +29,7 @@ fn get_col_pattern(terrain: &str) -> Vec<u128> {
     return ans;
 }
 
-fn get_reflection_line(pattern: Vec<u128>) -> usize {
+pub fn get_reflection_line(pattern: Vec<u128>) -> usize {
     'main_loop: for i in 1..pattern.len() {
         let iter1 = pattern.iter().take(i).rev();
         let iter2 = pattern.iter().skip(i);
@@ -41,7 +43,7 @@ fn get_reflection_line(pattern: Vec<u128>) -> usize {
     return 0;
 }
 
-fn get_smudged_reflection_line(pattern: Vec<u128>) -> usize {
+pub fn get_smudged_reflection_line(pattern: Vec<u128>) -> usize {
     'main_loop: for i in 1..pattern.len() {
         let iter1 = pattern.iter().take(i).rev();
         let iter2 = pattern.iter().skip(i);
@@ -61,11 +63,26 @@ fn get_smudged_reflection_line(pattern: Vec<u128>) -> usize {
     return 0;
 }
 
+pub fn get_terrains(document: &str) -> Result<Vec<&str>, PuzzleError> {
+    let terrains = document.split("\n\n").collect::<Vec<&str>>();
+    for terrain in &terrains {
+        let (height, width) = grid_size(terrain).map_err(|err| err.within(document, terrain))?;
+        if height.max(width) > 128 {
+            return Err(PuzzleError::unsupported(format!(
+                "patterns larger than 128 rows or columns (found {}x{})",
+                height, width
+            )));
+        }
+        check_chars(terrain, ".#").map_err(|err| err.within(document, terrain))?;
+    }
+    return Ok(terrains);
+}
+
 pub struct Day13Puzzle {}
-impl super::solve::Puzzle<String> for Day13Puzzle {
-    fn solve(&self, document: &str) -> String {
-        return document
-            .split("\n\n")
+impl super::solve::Puzzle for Day13Puzzle {
+    fn solve(&self, document: &str) -> Result<Answer, PuzzleError> {
+        return Ok(get_terrains(document)?
+            .iter()
             .map(|terrain| {
                 let row_pattern = get_row_pattern(terrain);
                 let col_pattern = get_col_pattern(terrain);
@@ -74,24 +91,33 @@ impl super::solve::Puzzle<String> for Day13Puzzle {
                 return col_reflection_line + 100 * row_reflection_line;
             })
             .sum::<usize>()
-            .to_string();
+            .into());
     }
 
-    fn solve2(&self, document: &str) -> String {
-        return document
-            .split("\n\n")
-            .map(|terrain| {
-                let row_pattern = get_row_pattern(terrain);
-                let col_pattern = get_col_pattern(terrain);
-                let row_reflection_line = get_smudged_reflection_line(row_pattern);
-                let col_reflection_line = get_smudged_reflection_line(col_pattern);
-                assert!((row_reflection_line != 0) ^ (col_reflection_line != 0));
-                return col_reflection_line + 100 * row_reflection_line;
-            })
-            .sum::<usize>()
-            .to_string();
+    fn solve2(&self, document: &str) -> Result<Answer, PuzzleError> {
+        let mut ans = 0_usize;
+        for (i, terrain) in get_terrains(document)?.iter().enumerate() {
+            let row_pattern = get_row_pattern(terrain);
+            let col_pattern = get_col_pattern(terrain);
+            let row_reflection_line = get_smudged_reflection_line(row_pattern);
+            let col_reflection_line = get_smudged_reflection_line(col_pattern);
+            if !((row_reflection_line != 0) ^ (col_reflection_line != 0)) {
+                return Err(PuzzleError::unsolvable(format!(
+                    "pattern {} does not have exactly one smudged reflection line",
+                    i + 1
+                )));
+            }
+            ans += col_reflection_line + 100 * row_reflection_line;
+        }
+        return Ok(ans.into());
     }
 }
+crate::register_puzzle!(
+    day: 13,
+    title: "Point of Incidence",
+    tags: ["grid"],
+    puzzle: Day13Puzzle {},
+);
 
 #[cfg(test)]
 mod tests {
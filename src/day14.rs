@@ -1,6 +1,9 @@
 use std::collections::HashMap;
 
-fn get_simplified_lane_load(lane: &str) -> u32 {
+use crate::solve::{check_chars, grid_size, Answer, PuzzleError};
+use crate::visualize::{Color, Frame, Visualize};
+
+pub fn get_simplified_lane_load(lane: &str) -> u32 {
     return lane
         .chars()
         .enumerate()
@@ -11,7 +14,7 @@ fn get_simplified_lane_load(lane: &str) -> u32 {
         .sum();
 }
 
-fn transpose(document: &mut Vec<String>) -> () {
+pub fn transpose(document: &mut Vec<String>) -> () {
     // assume document is square
     *document = (0..document.len())
         .map(|i| {
@@ -22,13 +25,13 @@ fn transpose(document: &mut Vec<String>) -> () {
         })
         .collect::<Vec<String>>();
 }
-fn flip(document: &mut Vec<String>) -> () {
+pub fn flip(document: &mut Vec<String>) -> () {
     *document = document
         .iter()
         .map(|line| line.chars().rev().collect::<String>())
         .collect::<Vec<String>>();
 }
-fn tilt_row_left(row: &mut String) -> () {
+pub fn tilt_row_left(row: &mut String) -> () {
     let old_row = row.clone();
     row.clear();
 
@@ -54,12 +57,23 @@ fn tilt_row_left(row: &mut String) -> () {
         row.push('.');
     }
 }
-fn tilt_board(board: &mut Vec<String>) -> () {
+pub fn tilt_board(board: &mut Vec<String>) -> () {
     for row in board {
         tilt_row_left(row);
     }
 }
-fn spin_cycle(board: &mut Vec<String>) -> () {
+pub fn get_board(document: &str) -> Result<Vec<String>, PuzzleError> {
+    let (height, width) = grid_size(document)?;
+    if height != width {
+        return Err(PuzzleError::unsupported(format!(
+            "only square platforms can be tilted (found {}x{})",
+            height, width
+        )));
+    }
+    check_chars(document, "O#.")?;
+    return Ok(document.lines().map(|line| line.to_string()).collect());
+}
+pub fn spin_cycle(board: &mut Vec<String>) -> () {
     // start: NESW
     transpose(board); // WSEN
     tilt_board(board); // N
@@ -77,30 +91,55 @@ fn spin_cycle(board: &mut Vec<String>) -> () {
     transpose(board); // NESW
 }
 
+/// Moves every rounded rock with empty space north of it one row north, returning whether any
+/// rock moved.
+pub fn roll_north_once(board: &mut [Vec<char>]) -> bool {
+    let mut moved = false;
+    for i in 1..board.len() {
+        for j in 0..board[i].len() {
+            if board[i][j] == 'O' && board[i - 1][j] == '.' {
+                board[i - 1][j] = 'O';
+                board[i][j] = '.';
+                moved = true;
+            }
+        }
+    }
+    return moved;
+}
+
+/// The total load on the north support beams of a board that is not transposed.
+pub fn get_north_load<S: AsRef<str>>(board: &[S]) -> usize {
+    return board
+        .iter()
+        .enumerate()
+        .map(|(i, row)| (board.len() - i) * row.as_ref().matches('O').count())
+        .sum();
+}
+
+pub fn board_frame<S: AsRef<str>>(board: &[S], caption: String) -> Frame {
+    let rows: Vec<&str> = board.iter().map(|row| row.as_ref()).collect();
+    let mut frame = Frame::from_grid(&rows.join("\n")).with_caption(caption);
+    frame.paint_all('O', Color::Yellow);
+    frame.paint_all('#', Color::Gray);
+    return frame;
+}
+
 pub struct Day14Puzzle {}
-impl super::solve::Puzzle<String> for Day14Puzzle {
-    fn solve(&self, document: &str) -> String {
-        let mut board: Vec<String> = document
-            .to_string()
-            .lines()
-            .map(|line| line.to_string())
-            .collect();
+impl super::solve::Puzzle for Day14Puzzle {
+    fn solve(&self, document: &str) -> Result<Answer, PuzzleError> {
+        let mut board: Vec<String> = get_board(document)?;
         transpose(&mut board);
         tilt_board(&mut board);
-        return board
+        return Ok(board
             .iter()
             .map(|lane| get_simplified_lane_load(lane))
             .sum::<u32>()
-            .to_string();
+            .into());
     }
 
-    fn solve2(&self, document: &str) -> String {
+    fn solve2(&self, document: &str) -> Result<Answer, PuzzleError> {
         let mut states: HashMap<Vec<String>, usize> = HashMap::new();
-        let mut board: Vec<String> = document
-            .to_string()
-            .lines()
-            .map(|line| line.to_string())
-            .collect();
+        let mut board: Vec<String> = get_board(document)?;
         let mut i = 0;
         loop {
             if states.contains_key(&board) {
@@ -123,11 +162,57 @@ impl super::solve::Puzzle<String> for Day14Puzzle {
 
         transpose(&mut board);
 
-        return board
+        return Ok(board
             .iter()
             .map(|lane| get_simplified_lane_load(lane))
             .sum::<u32>()
-            .to_string();
+            .into());
+    }
+
+    fn visualization(&self) -> Option<&dyn Visualize> {
+        return Some(self);
+    }
+}
+crate::register_puzzle!(
+    day: 14,
+    title: "Parabolic Reflector Dish",
+    tags: ["grid", "simulation", "cycles"],
+    puzzle: Day14Puzzle {},
+);
+
+/// Part 1 rolls the rocks north one row per frame. Part 2 shows the platform after each spin
+/// cycle until it repeats a state.
+impl Visualize for Day14Puzzle {
+    fn frames(&self, document: &str, part: i32) -> Result<Vec<Frame>, PuzzleError> {
+        let mut board = get_board(document)?;
+        let mut frames = vec![board_frame(
+            &board,
+            format!("start, load {}", get_north_load(&board)),
+        )];
+        if part == 1 {
+            let mut grid: Vec<Vec<char>> = board.iter().map(|row| row.chars().collect()).collect();
+            let mut step = 0;
+            while roll_north_once(&mut grid) {
+                step += 1;
+                let rows: Vec<String> = grid.iter().map(|row| row.iter().collect()).collect();
+                let caption = format!("step {}, load {}", step, get_north_load(&rows));
+                frames.push(board_frame(&rows, caption));
+            }
+            return Ok(frames);
+        }
+
+        let mut seen: HashMap<Vec<String>, usize> = HashMap::new();
+        let mut cycle = 0;
+        while !seen.contains_key(&board) {
+            seen.insert(board.clone(), cycle);
+            spin_cycle(&mut board);
+            cycle += 1;
+            let caption = format!("cycle {}, load {}", cycle, get_north_load(&board));
+            frames.push(board_frame(&board, caption));
+        }
+        frames.last_mut().unwrap().caption +=
+            &format!(", same as after cycle {}", seen.get(&board).unwrap());
+        return Ok(frames);
     }
 }
 
@@ -169,4 +254,13 @@ mod tests {
         tilt_row_left(&mut row);
         assert_eq!(row, String::from("O..#OOO........#O"));
     }
+
+    #[test]
+    fn test_roll_north_once() {
+        let mut board: Vec<Vec<char>> = vec![vec!['.', '.'], vec!['O', '#'], vec!['O', '.']];
+        assert!(roll_north_once(&mut board));
+        assert_eq!(board, vec![vec!['O', '.'], vec!['O', '#'], vec!['.', '.']]);
+        assert!(!roll_north_once(&mut board));
+        assert_eq!(get_north_load(&["O.", "O#", ".."]), 5);
+    }
 }
@@ -1,6 +1,8 @@
 use std::collections::HashMap;
 
-fn hash_string(input: &str) -> u8 {
+use crate::solve::{parse_token, Answer, PuzzleError};
+
+pub fn hash_string(input: &str) -> u8 {
     return input
         .chars()
         .map(|c| c as u8)
@@ -8,20 +10,21 @@ fn hash_string(input: &str) -> u8 {
 }
 
 #[derive(Debug, Clone)]
-struct LensBox {
-    lenses: Vec<usize>,
-    lens_pos: HashMap<String, usize>,
-    i: usize,
+pub struct LensBox {
+    pub lenses: Vec<usize>,
+    pub lens_pos: HashMap<String, usize>,
+    pub i: usize,
 }
 
-fn execute_lens_command(boxes: &mut Vec<LensBox>, command: &str) -> () {
-    match command.chars().last().unwrap() {
-        '-' => execute_subtract_command(boxes, command),
-        _ => execute_set_command(boxes, command),
+pub fn execute_lens_command(boxes: &mut Vec<LensBox>, command: &str) -> Result<(), PuzzleError> {
+    match command.chars().last() {
+        Some('-') => execute_subtract_command(boxes, command),
+        _ => execute_set_command(boxes, command)?,
     }
+    return Ok(());
 }
 
-fn execute_subtract_command(boxes: &mut Vec<LensBox>, command: &str) -> () {
+pub fn execute_subtract_command(boxes: &mut Vec<LensBox>, command: &str) -> () {
     let name = &command[..command.len() - 1];
     let hash = hash_string(name);
     let active_box = &mut boxes[hash as usize];
@@ -35,15 +38,15 @@ fn execute_subtract_command(boxes: &mut Vec<LensBox>, command: &str) -> () {
     active_box.lens_pos.remove(name);
 }
 
-fn execute_set_command(boxes: &mut Vec<LensBox>, command: &str) -> () {
-    let name = command.split('=').into_iter().nth(0).unwrap();
-    let lens_number = command
-        .split('=')
-        .into_iter()
-        .nth(1)
-        .unwrap()
-        .parse::<usize>()
-        .unwrap();
+pub fn execute_set_command(boxes: &mut Vec<LensBox>, command: &str) -> Result<(), PuzzleError> {
+    let Some((name, lens_number)) = command.split_once('=') else {
+        return Err(PuzzleError::parse(
+            command,
+            command,
+            format!("invalid step `{}`", command),
+        ));
+    };
+    let lens_number = parse_token::<usize>(command, lens_number)?;
 
     let hash = hash_string(name);
     let active_box = &mut boxes[hash as usize];
@@ -56,9 +59,10 @@ fn execute_set_command(boxes: &mut Vec<LensBox>, command: &str) -> () {
         active_box.lens_pos.insert(name.to_string(), active_box.i);
         active_box.i += 1;
     }
+    return Ok(());
 }
 
-fn get_box_power(lens_box: &LensBox) -> i32 {
+pub fn get_box_power(lens_box: &LensBox) -> i32 {
     return lens_box
         .lenses
         .iter()
@@ -68,17 +72,21 @@ fn get_box_power(lens_box: &LensBox) -> i32 {
         .sum();
 }
 
+/// The comma separated steps of the initialization sequence, ignoring the trailing newline.
+pub fn get_steps(document: &str) -> std::str::Split<'_, char> {
+    return document.trim_end_matches(['\n', '\r']).split(',');
+}
+
 pub struct Day15Puzzle {}
-impl super::solve::Puzzle<String> for Day15Puzzle {
-    fn solve(&self, document: &str) -> String {
-        return document
-            .split(',')
+impl super::solve::Puzzle for Day15Puzzle {
+    fn solve(&self, document: &str) -> Result<Answer, PuzzleError> {
+        return Ok(get_steps(document)
             .map(|x| hash_string(x) as i32)
             .sum::<i32>()
-            .to_string();
+            .into());
     }
 
-    fn solve2(&self, document: &str) -> String {
+    fn solve2(&self, document: &str) -> Result<Answer, PuzzleError> {
         let mut boxes = vec![
             LensBox {
                 lenses: Vec::new(),
@@ -88,18 +96,20 @@ impl super::solve::Puzzle<String> for Day15Puzzle {
             256
         ];
 
-        for command in document.split(',') {
-            execute_lens_command(&mut boxes, command);
+        for command in get_steps(document) {
+            execute_lens_command(&mut boxes, command)
+                .map_err(|err| err.within(document, command))?;
         }
 
-        return boxes
+        return Ok(boxes
             .iter()
             .enumerate()
             .map(|(i, x)| (i as i32 + 1) * get_box_power(x))
             .sum::<i32>()
-            .to_string();
+            .into());
     }
 }
+crate::register_puzzle!(day: 15, title: "Lens Library", tags: ["hashing"], puzzle: Day15Puzzle {});
 
 #[cfg(test)]
 mod tests {
@@ -120,20 +130,22 @@ mod tests {
             };
             256
         ];
-        execute_lens_command(boxes, "rn=1");
+        execute_lens_command(boxes, "rn=1").unwrap();
         assert_eq!(boxes[0].lenses, vec![1]);
         assert_eq!(boxes[0].lens_pos.get("rn"), Some(&0));
-        execute_lens_command(boxes, "rn=2");
+        execute_lens_command(boxes, "rn=2").unwrap();
         assert_eq!(boxes[0].lenses, vec![2]);
         assert_eq!(boxes[0].lens_pos.get("rn"), Some(&0));
-        execute_lens_command(boxes, "qp=3");
+        execute_lens_command(boxes, "qp=3").unwrap();
         assert_eq!(boxes[1].lenses, vec![3]);
         assert_eq!(boxes[1].lens_pos.get("qp"), Some(&0));
-        execute_lens_command(boxes, "qp-");
+        execute_lens_command(boxes, "qp-").unwrap();
         assert_eq!(boxes[1].lenses, vec![0]);
         assert_eq!(boxes[1].lens_pos.get("qp"), None);
-        execute_lens_command(boxes, "qp=3");
+        execute_lens_command(boxes, "qp=3").unwrap();
         assert_eq!(boxes[1].lenses, vec![0, 3]);
         assert_eq!(boxes[1].lens_pos.get("qp"), Some(&1));
+        assert!(execute_lens_command(boxes, "qp=x").is_err());
+        assert!(execute_lens_command(boxes, "qp").is_err());
     }
 }
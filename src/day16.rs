@@ -1,14 +1,20 @@
 use std::collections::HashSet;
 
+use crate::solve::{check_chars, grid_size, Answer, PuzzleError};
+use crate::visualize::{Color, Frame, Visualize};
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
-enum Direction {
+pub enum Direction {
     N,
     E,
     S,
     W,
 }
 
-fn get_next_position(position: ((i32, i32), Direction), env: char) -> Vec<((i32, i32), Direction)> {
+pub fn get_next_position(
+    position: ((i32, i32), Direction),
+    env: char,
+) -> Vec<((i32, i32), Direction)> {
     let ((x, y), direction) = position;
 
     let n_move = ((x - 1, y), Direction::N);
@@ -55,7 +61,12 @@ fn get_next_position(position: ((i32, i32), Direction), env: char) -> Vec<((i32,
     panic!("Invalid environment");
 }
 
-fn get_energized_tiles(document: &str, start: ((i32, i32), Direction)) -> usize {
+/// Follows the beam entering at `start` and returns the tiles and directions it newly reaches on
+/// each step, until it only revisits tiles in directions it already went.
+pub fn trace_beam(
+    document: &str,
+    start: ((i32, i32), Direction),
+) -> Vec<Vec<((i32, i32), Direction)>> {
     let board = document
         .lines()
         .map(|x| x.chars().collect::<Vec<char>>())
@@ -63,10 +74,12 @@ fn get_energized_tiles(document: &str, start: ((i32, i32), Direction)) -> usize
     let m = board.len();
     let n = board[0].len();
 
+    let mut steps = Vec::new();
     let mut positions = vec![start];
     let mut visited: HashSet<((i32, i32), Direction)> = HashSet::new();
 
     while positions.len() > 0 {
+        let mut reached = Vec::new();
         let mut next_positions = Vec::new();
         for position in positions {
             let ((x, y), _) = position;
@@ -78,41 +91,124 @@ fn get_energized_tiles(document: &str, start: ((i32, i32), Direction)) -> usize
                 continue;
             }
             visited.insert(position);
+            reached.push(position);
 
             let env = board[x as usize][y as usize];
             let mut next_positions_for_position = get_next_position(position, env);
             next_positions.append(&mut next_positions_for_position);
         }
+        if reached.len() > 0 {
+            steps.push(reached);
+        }
         positions = next_positions;
     }
-    return visited
+    return steps;
+}
+
+pub fn get_energized_tiles(document: &str, start: ((i32, i32), Direction)) -> usize {
+    return trace_beam(document, start)
         .iter()
+        .flatten()
         .map(|x| x.0)
         .collect::<HashSet<(i32, i32)>>()
         .len();
 }
 
+/// Every tile on the edge of an `m` by `n` board, facing into the board.
+pub fn get_edge_starts(m: i32, n: i32) -> Vec<((i32, i32), Direction)> {
+    let mut starts = Vec::new();
+    for i in 0..m {
+        starts.push(((i, 0), Direction::E));
+        starts.push(((i, n - 1), Direction::W));
+    }
+    for j in 0..n {
+        starts.push(((0, j), Direction::S));
+        starts.push(((m - 1, j), Direction::N));
+    }
+    return starts;
+}
+
 pub struct Day16Puzzle {}
-impl super::solve::Puzzle<String> for Day16Puzzle {
-    fn solve(&self, document: &str) -> String {
-        return get_energized_tiles(document, ((0, 0), Direction::E)).to_string();
+impl super::solve::Puzzle for Day16Puzzle {
+    fn solve(&self, document: &str) -> Result<Answer, PuzzleError> {
+        grid_size(document)?;
+        check_chars(document, ".\\/-|")?;
+        return Ok(get_energized_tiles(document, ((0, 0), Direction::E)).into());
+    }
+
+    fn solve2(&self, document: &str) -> Result<Answer, PuzzleError> {
+        let (m, n) = grid_size(document)?;
+        check_chars(document, ".\\/-|")?;
+
+        let ans = get_edge_starts(m as i32, n as i32)
+            .into_iter()
+            .map(|start| get_energized_tiles(document, start))
+            .max()
+            .unwrap_or(0);
+
+        return Ok(ans.into());
     }
 
-    fn solve2(&self, document: &str) -> String {
-        let m = document.lines().count() as i32;
-        let n = document.lines().next().unwrap().chars().count() as i32;
+    fn visualization(&self) -> Option<&dyn Visualize> {
+        return Some(self);
+    }
+}
+crate::register_puzzle!(
+    day: 16,
+    title: "The Floor Will Be Lava",
+    tags: ["grid", "simulation"],
+    puzzle: Day16Puzzle {},
+);
+
+/// Spreads the beam one tile per frame, from the top left corner for part 1 and from the start
+/// that energizes the most tiles for part 2. Energized tiles are yellow and the front of the beam
+/// is red, with arrows on empty tiles.
+impl Visualize for Day16Puzzle {
+    fn frames(&self, document: &str, part: i32) -> Result<Vec<Frame>, PuzzleError> {
+        let (m, n) = grid_size(document)?;
+        check_chars(document, ".\\/-|")?;
+        let start = match part {
+            1 => ((0, 0), Direction::E),
+            _ => get_edge_starts(m as i32, n as i32)
+                .into_iter()
+                .max_by_key(|&start| get_energized_tiles(document, start))
+                .unwrap(),
+        };
 
-        let mut ans = 0;
-        for i in 0..m {
-            ans = ans.max(get_energized_tiles(document, ((i, 0), Direction::E)));
-            ans = ans.max(get_energized_tiles(document, ((i, n - 1), Direction::W)));
+        let tiles = Frame::from_grid(document);
+        let mut frame = tiles.clone();
+        for ch in ['\\', '/', '-', '|'] {
+            frame.paint_all(ch, Color::Gray);
         }
-        for j in 0..n {
-            ans = ans.max(get_energized_tiles(document, ((0, j), Direction::S)));
-            ans = ans.max(get_energized_tiles(document, ((m - 1, j), Direction::N)));
+        let mut frames = vec![frame.clone().with_caption("start")];
+        let mut energized: HashSet<(i32, i32)> = HashSet::new();
+        let mut front: Vec<((i32, i32), Direction)> = Vec::new();
+        for (step, reached) in trace_beam(document, start).into_iter().enumerate() {
+            for ((x, y), _) in front {
+                let (x, y) = (x as usize, y as usize);
+                let ch = match tiles.get(x, y).ch {
+                    '.' => '#',
+                    ch => ch,
+                };
+                frame.set(x, y, ch, Some(Color::Yellow));
+            }
+            for &((x, y), direction) in &reached {
+                energized.insert((x, y));
+                let (x, y) = (x as usize, y as usize);
+                let ch = match (tiles.get(x, y).ch, direction) {
+                    ('.', Direction::N) => '^',
+                    ('.', Direction::E) => '>',
+                    ('.', Direction::S) => 'v',
+                    ('.', Direction::W) => '<',
+                    (ch, _) => ch,
+                };
+                frame.set(x, y, ch, Some(Color::Red));
+            }
+            let caption = format!("step {}, {} tiles energized", step + 1, energized.len());
+            frames.push(frame.clone().with_caption(caption));
+            front = reached;
         }
-
-        return ans.to_string();
+        return Ok(frames);
     }
 }
 
@@ -146,4 +242,16 @@ mod tests {
         let energized_tiles = get_energized_tiles(document, ((0, 0), Direction::E));
         assert_eq!(energized_tiles, 3);
     }
+
+    #[test]
+    fn test_trace_beam() {
+        assert_eq!(
+            trace_beam("|.\n\\.", ((0, 0), Direction::E)),
+            vec![
+                vec![((0, 0), Direction::E)],
+                vec![((1, 0), Direction::S)],
+                vec![((1, 1), Direction::E)],
+            ]
+        );
+    }
 }
@@ -3,19 +3,23 @@ use std::{
     collections::{BinaryHeap, HashSet},
 };
 
-fn get_grid(document: &str) -> Vec<Vec<usize>> {
-    return document
+use crate::solve::{check_chars, grid_size, Answer, PuzzleError};
+
+pub fn get_grid(document: &str) -> Result<Vec<Vec<usize>>, PuzzleError> {
+    grid_size(document)?;
+    check_chars(document, "0123456789")?;
+    return Ok(document
         .lines()
         .map(|line| {
             line.chars()
-                .map(|c| c.to_string().parse::<usize>().unwrap())
+                .map(|c| c.to_digit(10).unwrap() as usize)
                 .collect()
         })
-        .collect();
+        .collect());
 }
 
 #[derive(Debug, PartialEq, Clone, Copy, Eq, Hash)]
-enum Direction {
+pub enum Direction {
     N,
     E,
     S,
@@ -32,10 +36,10 @@ impl Direction {
     }
 }
 #[derive(Debug, PartialEq, Clone, Copy, Eq, Hash)]
-struct State {
-    pos: (isize, isize),
-    direction: Direction,
-    steps: usize,
+pub struct State {
+    pub pos: (isize, isize),
+    pub direction: Direction,
+    pub steps: usize,
 }
 impl Ord for State {
     fn cmp(&self, _other: &Self) -> Ordering {
@@ -48,7 +52,7 @@ impl PartialOrd for State {
     }
 }
 
-fn get_next_steps(state: State, bounds: (isize, isize)) -> Vec<State> {
+pub fn get_next_steps(state: State, bounds: (isize, isize)) -> Vec<State> {
     let (x, y) = state.pos;
     let (max_x, max_y) = bounds;
 
@@ -84,7 +88,7 @@ fn get_next_steps(state: State, bounds: (isize, isize)) -> Vec<State> {
     return next_states;
 }
 
-fn get_next_steps_2(state: State, bounds: (isize, isize)) -> Vec<State> {
+pub fn get_next_steps_2(state: State, bounds: (isize, isize)) -> Vec<State> {
     let (x, y) = state.pos;
     let (max_x, max_y) = bounds;
 
@@ -123,12 +127,12 @@ fn get_next_steps_2(state: State, bounds: (isize, isize)) -> Vec<State> {
     return next_states;
 }
 
-fn get_fastest_path(
+pub fn get_fastest_path(
     document: &str,
     step_generator: &dyn Fn(State, (isize, isize)) -> Vec<State>,
     completion_condition: &dyn Fn(State) -> bool,
-) -> String {
-    let grid = get_grid(document);
+) -> Result<isize, PuzzleError> {
+    let grid = get_grid(document)?;
     let bounds = (grid.len() as isize, grid[0].len() as isize);
 
     let mut queue: BinaryHeap<(isize, State)> = BinaryHeap::new();
@@ -142,10 +146,9 @@ fn get_fastest_path(
         },
     ));
 
-    loop {
-        let (cost, state) = queue.pop().unwrap();
+    while let Some((cost, state)) = queue.pop() {
         if state.pos == (bounds.0 - 1, bounds.1 - 1) && completion_condition(state) {
-            return (-cost).to_string();
+            return Ok(-cost);
         }
         if visited.contains(&state) {
             continue;
@@ -160,18 +163,29 @@ fn get_fastest_path(
             ));
         }
     }
+    return Err(PuzzleError::unsolvable(
+        "the crucible cannot reach the bottom-right block",
+    ));
 }
 
 pub struct Day17Puzzle {}
-impl super::solve::Puzzle<String> for Day17Puzzle {
-    fn solve(&self, document: &str) -> String {
-        return get_fastest_path(document, &get_next_steps, &|_| true);
+impl super::solve::Puzzle for Day17Puzzle {
+    fn solve(&self, document: &str) -> Result<Answer, PuzzleError> {
+        return Ok(get_fastest_path(document, &get_next_steps, &|_| true)?.into());
     }
 
-    fn solve2(&self, document: &str) -> String {
-        return get_fastest_path(document, &get_next_steps_2, &|state| state.steps >= 4);
+    fn solve2(&self, document: &str) -> Result<Answer, PuzzleError> {
+        return Ok(
+            get_fastest_path(document, &get_next_steps_2, &|state| state.steps >= 4)?.into(),
+        );
     }
 }
+crate::register_puzzle!(
+    day: 17,
+    title: "Clumsy Crucible",
+    tags: ["grid", "shortest-path"],
+    puzzle: Day17Puzzle {},
+);
 
 #[cfg(test)]
 mod tests {
@@ -180,8 +194,12 @@ mod tests {
     #[test]
     fn test_get_grid() {
         let document = "123\n456\n789";
-        let grid = get_grid(document);
+        let grid = get_grid(document).unwrap();
         assert_eq!(grid, vec![vec![1, 2, 3], vec![4, 5, 6], vec![7, 8, 9]]);
+        assert_eq!(
+            get_grid("123\n4x6").unwrap_err().to_string(),
+            "parse error at line 2, column 2: unexpected character `x`"
+        );
     }
 
     #[test]
@@ -1,49 +1,77 @@
+use crate::solve::{parse_token, Answer, PuzzleError};
+
 #[derive(Debug, PartialEq, Clone, Copy, Eq, Hash)]
-enum Direction {
+pub enum Direction {
     U,
     R,
     D,
     L,
 }
 
-fn get_line_instruction(line: &str) -> (u64, Direction) {
-    let dir = match line.chars().nth(0).unwrap() {
-        'U' => Direction::U,
-        'R' => Direction::R,
-        'D' => Direction::D,
-        'L' => Direction::L,
-        _ => panic!("Invalid direction"),
+pub fn get_line_instruction(line: &str) -> Result<(u64, Direction), PuzzleError> {
+    let mut fields = line.split(' ');
+    let direction = fields.next().unwrap();
+    let dir = match direction {
+        "U" => Direction::U,
+        "R" => Direction::R,
+        "D" => Direction::D,
+        "L" => Direction::L,
+        _ => {
+            return Err(PuzzleError::parse(
+                line,
+                direction,
+                format!("invalid direction `{}`", direction),
+            ))
+        }
+    };
+    let Some(steps) = fields.next() else {
+        return Err(PuzzleError::missing(line, line, "missing step count"));
     };
-    let steps = line.split(' ').nth(1).unwrap().parse::<u64>().unwrap();
-    return (steps, dir);
+    return Ok((parse_token::<u64>(line, steps)?, dir));
 }
 
-fn get_updated_line_instruction(line: &str) -> (u64, Direction) {
-    let color = line.split("#").nth(1).unwrap()[0..6].to_string();
+pub fn get_updated_line_instruction(line: &str) -> Result<(u64, Direction), PuzzleError> {
+    let color = match line.split_once('#') {
+        Some((_, rest)) if rest.len() >= 6 && rest.is_char_boundary(6) => &rest[0..6],
+        _ => {
+            return Err(PuzzleError::missing(
+                line,
+                line,
+                "missing `#rrggbb` color code",
+            ))
+        }
+    };
 
-    let distance = u64::from_str_radix(&color[0..5], 16).unwrap();
-    let dir = match color.chars().nth(5).unwrap() {
-        '0' => Direction::R,
-        '1' => Direction::D,
-        '2' => Direction::L,
-        '3' => Direction::U,
-        _ => panic!("Invalid direction"),
+    let distance = u64::from_str_radix(&color[0..5], 16)
+        .map_err(|_| PuzzleError::parse(line, color, format!("invalid color code `#{}`", color)))?;
+    let dir = match &color[5..6] {
+        "0" => Direction::R,
+        "1" => Direction::D,
+        "2" => Direction::L,
+        "3" => Direction::U,
+        direction => {
+            return Err(PuzzleError::parse(
+                line,
+                direction,
+                format!("invalid direction digit `{}`", direction),
+            ))
+        }
     };
 
-    return (distance, dir);
+    return Ok((distance, dir));
 }
 
-fn get_instructions(
+pub fn get_instructions(
     document: &str,
-    get_line_instruction: &dyn Fn(&str) -> (u64, Direction),
-) -> Vec<(u64, Direction)> {
+    get_line_instruction: &dyn Fn(&str) -> Result<(u64, Direction), PuzzleError>,
+) -> Result<Vec<(u64, Direction)>, PuzzleError> {
     return document
         .lines()
-        .map(|line| get_line_instruction(line))
+        .map(|line| get_line_instruction(line).map_err(|err| err.within(document, line)))
         .collect();
 }
 
-fn get_point_sequence(instructions: Vec<(u64, Direction)>) -> Vec<(isize, isize)> {
+pub fn get_point_sequence(instructions: Vec<(u64, Direction)>) -> Vec<(isize, isize)> {
     let mut points: Vec<(isize, isize)> = Vec::new();
     let mut point = (0, 0);
     points.push(point);
@@ -59,7 +87,7 @@ fn get_point_sequence(instructions: Vec<(u64, Direction)>) -> Vec<(isize, isize)
     return points;
 }
 
-fn shoelace_area(points: &Vec<(isize, isize)>) -> isize {
+pub fn shoelace_area(points: &Vec<(isize, isize)>) -> isize {
     let mut area = 0;
     for pair in points.windows(2) {
         let (x0, y0) = pair[0];
@@ -70,27 +98,33 @@ fn shoelace_area(points: &Vec<(isize, isize)>) -> isize {
 }
 
 pub struct Day18Puzzle {}
-impl super::solve::Puzzle<String> for Day18Puzzle {
-    fn solve(&self, document: &str) -> String {
-        let instructions = get_instructions(document, &get_line_instruction);
+impl super::solve::Puzzle for Day18Puzzle {
+    fn solve(&self, document: &str) -> Result<Answer, PuzzleError> {
+        let instructions = get_instructions(document, &get_line_instruction)?;
 
         let bondary_size = instructions.iter().map(|(steps, _)| steps).sum::<u64>() / 2;
 
         let points = get_point_sequence(instructions);
         let area = shoelace_area(&points) + bondary_size as isize + 1;
-        return area.to_string();
+        return Ok(area.into());
     }
 
-    fn solve2(&self, document: &str) -> String {
-        let instructions = get_instructions(document, &get_updated_line_instruction);
+    fn solve2(&self, document: &str) -> Result<Answer, PuzzleError> {
+        let instructions = get_instructions(document, &get_updated_line_instruction)?;
 
         let bondary_size = instructions.iter().map(|(steps, _)| steps).sum::<u64>() / 2;
 
         let points = get_point_sequence(instructions);
         let area = shoelace_area(&points) + bondary_size as isize + 1;
-        return area.to_string();
+        return Ok(area.into());
     }
 }
+crate::register_puzzle!(
+    day: 18,
+    title: "Lavaduct Lagoon",
+    tags: ["geometry"],
+    puzzle: Day18Puzzle {},
+);
 
 #[cfg(test)]
 mod tests {
@@ -98,6 +132,19 @@ mod tests {
 
     #[test]
     fn test_get_line_instruction() {
-        assert_eq!(get_line_instruction("D 10 (#6cc0d3)"), (10, Direction::D));
+        assert_eq!(
+            get_line_instruction("D 10 (#6cc0d3)"),
+            Ok((10, Direction::D))
+        );
+        assert_eq!(
+            get_updated_line_instruction("D 10 (#6cc0d3)"),
+            Ok((445453, Direction::U))
+        );
+        assert_eq!(
+            get_instructions("R 6 (#70c710)\nX 5 (#0dc571)", &get_line_instruction)
+                .unwrap_err()
+                .to_string(),
+            "parse error at line 2, column 1: invalid direction `X`"
+        );
     }
 }
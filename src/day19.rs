@@ -1,11 +1,13 @@
 use std::collections::HashMap;
 
+use crate::solve::{parse_token, Answer, PuzzleError};
+
 #[derive(Debug, Clone, Copy)]
-struct Part {
-    x: usize,
-    m: usize,
-    a: usize,
-    s: usize,
+pub struct Part {
+    pub x: usize,
+    pub m: usize,
+    pub a: usize,
+    pub s: usize,
 }
 impl Part {
     fn score(&self) -> usize {
@@ -13,106 +15,168 @@ impl Part {
     }
 }
 
-type PartAcceptor = dyn Fn(Part) -> bool;
-type Workflow<'a> = Vec<(Box<PartAcceptor>, &'a str)>;
-
-fn parse_part(line: &str) -> Part {
-    let clean_line = line.strip_prefix('{').unwrap().strip_suffix('}').unwrap();
-    let mut parts = clean_line
-        .split(',')
-        .map(|part| part[2..].parse::<usize>().unwrap());
-    let x = parts.next().unwrap();
-    let m = parts.next().unwrap();
-    let a = parts.next().unwrap();
-    let s = parts.next().unwrap();
-    return Part { x, m, a, s };
+pub type PartAcceptor = dyn Fn(Part) -> bool;
+pub type Workflow<'a> = Vec<(Box<PartAcceptor>, &'a str)>;
+
+pub fn parse_part(line: &str) -> Result<Part, PuzzleError> {
+    let Some(clean_line) = line.strip_prefix('{').and_then(|l| l.strip_suffix('}')) else {
+        return Err(PuzzleError::parse(line, line, "expected a part in braces"));
+    };
+    let ratings = clean_line.split(',').collect::<Vec<&str>>();
+    let mut values = [0_usize; 4];
+    if ratings.len() != 4 {
+        return Err(PuzzleError::parse(
+            line,
+            clean_line,
+            format!("expected 4 ratings, found {}", ratings.len()),
+        ));
+    }
+    for ((rating, category), value) in ratings
+        .iter()
+        .zip(["x=", "m=", "a=", "s="])
+        .zip(&mut values)
+    {
+        let Some(number) = rating.strip_prefix(category) else {
+            return Err(PuzzleError::parse(
+                line,
+                rating,
+                format!("expected rating `{}`", category),
+            ));
+        };
+        *value = parse_token::<usize>(line, number)?;
+    }
+    let [x, m, a, s] = values;
+    return Ok(Part { x, m, a, s });
 }
 
-fn parse_workflow(line: &'static str) -> (&str, Workflow) {
-    let name = line.split("{").nth(0).unwrap();
-    let rules = line
-        .split("{")
-        .nth(1)
-        .unwrap()
-        .strip_suffix('}')
-        .unwrap()
-        .split(",");
-
-    let workflow = rules
-        .map(|rule| {
-            let parts = rule.split(':').collect::<Vec<&str>>();
-            match parts.len() {
-                1 => (Box::new(|_| true) as Box<PartAcceptor>, parts[0]),
-                2 => (parse_condition(parts[0]), parts[1]),
-                _ => panic!("Invalid rule"),
-            }
-        })
-        .collect();
+pub fn parse_workflow(line: &str) -> Result<(&str, Workflow<'_>), PuzzleError> {
+    let Some((name, rules)) = line
+        .split_once('{')
+        .and_then(|(name, rules)| Some((name, rules.strip_suffix('}')?)))
+    else {
+        return Err(PuzzleError::parse(line, line, "expected `name{rules}`"));
+    };
+
+    let mut workflow: Workflow = Vec::new();
+    for rule in rules.split(',') {
+        match rule.split_once(':') {
+            None => workflow.push((Box::new(|_| true), rule)),
+            Some((condition, destination)) => workflow.push((
+                parse_condition(condition).map_err(|err| err.within(line, condition))?,
+                destination,
+            )),
+        }
+    }
+    if rules.split(',').last().unwrap().contains(':') {
+        return Err(PuzzleError::missing(
+            line,
+            rules,
+            "workflow does not end with a fallback rule",
+        ));
+    }
 
-    return (name, workflow);
+    return Ok((name, workflow));
 }
-fn parse_condition(condition: &'static str) -> Box<PartAcceptor> {
-    let value = condition[2..].parse::<usize>().unwrap();
+
+/// Splits a condition like `a<2006` into its category, comparer and value.
+pub fn split_condition(condition: &str) -> Result<(&str, &str, usize), PuzzleError> {
+    if condition.len() < 3 || !condition.is_char_boundary(1) || !condition.is_char_boundary(2) {
+        return Err(PuzzleError::parse(
+            condition,
+            condition,
+            format!("invalid condition `{}`", condition),
+        ));
+    }
+    let (component, comparer, value) = (&condition[0..1], &condition[1..2], &condition[2..]);
+    if !"xmas".contains(component) {
+        return Err(PuzzleError::parse(
+            condition,
+            component,
+            format!("invalid category `{}`", component),
+        ));
+    }
+    if comparer != "<" && comparer != ">" {
+        return Err(PuzzleError::parse(
+            condition,
+            comparer,
+            format!("invalid comparer `{}`", comparer),
+        ));
+    }
+    return Ok((component, comparer, parse_token::<usize>(condition, value)?));
+}
+
+pub fn parse_condition(condition: &str) -> Result<Box<PartAcceptor>, PuzzleError> {
+    let (component, comparer, value) = split_condition(condition)?;
+    let is_less = comparer == "<";
 
     let comparer = move |v: usize| -> bool {
-        match &condition[1..2] {
-            "<" => v < value,
-            ">" => v > value,
-            _ => panic!("Invalid comparer"),
+        match is_less {
+            true => v < value,
+            false => v > value,
         }
     };
 
-    return Box::new(move |part: Part| -> bool {
-        match &condition[0..1] {
-            "x" => comparer(part.x),
-            "m" => comparer(part.m),
-            "a" => comparer(part.a),
-            "s" => comparer(part.s),
-            _ => panic!("Invalid category"),
+    let component = component.chars().next().unwrap();
+    return Ok(Box::new(move |part: Part| -> bool {
+        match component {
+            'x' => comparer(part.x),
+            'm' => comparer(part.m),
+            'a' => comparer(part.a),
+            _ => comparer(part.s),
         }
-    });
+    }));
 }
 
-fn execute_workflow(workflow: &Workflow, part: Part) -> String {
+pub fn execute_workflow<'a>(workflow: &Workflow<'a>, part: Part) -> &'a str {
     for (acceptor, name) in workflow {
         if acceptor(part) {
-            return name.to_string();
+            return name;
         }
     }
-    panic!("No matching rule found");
+    unreachable!("workflows always end with a fallback rule");
 }
 
-fn sort_part(
+pub fn sort_part(
     workflows: &HashMap<&str, Workflow>,
     part: Part,
     accepted: &mut Vec<Part>,
     rejected: &mut Vec<Part>,
-) {
+) -> Result<(), PuzzleError> {
     let mut active: &str = "in";
-    let mut next_active: String;
     loop {
-        let workflow = workflows.get(active).unwrap();
-        next_active = execute_workflow(workflow, part);
-        match next_active.as_str() {
+        let Some(workflow) = workflows.get(active) else {
+            return Err(PuzzleError::unsolvable(format!(
+                "unknown workflow `{}`",
+                active
+            )));
+        };
+        match execute_workflow(workflow, part) {
             "A" => {
                 accepted.push(part);
-                return;
+                return Ok(());
             }
             "R" => {
                 rejected.push(part);
-                return;
+                return Ok(());
             }
             x @ _ => active = x,
         }
     }
 }
 
+/// Splits the document into its workflow and part ratings sections.
+pub fn split_system(document: &str) -> Result<(&str, &str), PuzzleError> {
+    return document
+        .split_once("\n\n")
+        .ok_or_else(|| PuzzleError::missing(document, document, "missing part ratings section"));
+}
+
 #[derive(Debug, Clone, Copy)]
-struct PartRange {
-    x: (usize, usize),
-    m: (usize, usize),
-    a: (usize, usize),
-    s: (usize, usize),
+pub struct PartRange {
+    pub x: (usize, usize),
+    pub m: (usize, usize),
+    pub a: (usize, usize),
+    pub s: (usize, usize),
 }
 impl PartRange {
     fn size(&self) -> usize {
@@ -161,10 +225,8 @@ impl PartRange {
     }
 }
 
-fn apply_condition_to_range(condition: &str, part_range: &PartRange) -> (PartRange, PartRange) {
-    let value = condition[2..].parse::<usize>().unwrap();
-    let comparer = &condition[1..2];
-    let component = &condition[0..1];
+pub fn apply_condition_to_range(condition: &str, part_range: &PartRange) -> (PartRange, PartRange) {
+    let (component, comparer, value) = split_condition(condition).unwrap();
 
     let comp_val = part_range.get_comp(component);
     let (good_val, bad_val) = match comparer {
@@ -184,7 +246,10 @@ fn apply_condition_to_range(condition: &str, part_range: &PartRange) -> (PartRan
     return (good_range, bad_range);
 }
 
-fn apply_workflow_to_range(workflow: &str, mut part_range: PartRange) -> Vec<(String, PartRange)> {
+pub fn apply_workflow_to_range(
+    workflow: &str,
+    mut part_range: PartRange,
+) -> Vec<(String, PartRange)> {
     let mut result: Vec<(String, PartRange)> = Vec::new();
     let rules = workflow.split(",");
     let mut bad: PartRange;
@@ -217,37 +282,37 @@ fn apply_workflow_to_range(workflow: &str, mut part_range: PartRange) -> Vec<(St
 }
 
 pub struct Day19Puzzle {}
-impl super::solve::Puzzle<String> for Day19Puzzle {
-    fn solve(&self, document: &str) -> String {
-        let static_doc = Box::leak(document.to_string().into_boxed_str());
-
-        let mut chunks = static_doc.split("\n\n");
-        let workflows = chunks
-            .next()
-            .unwrap()
+impl super::solve::Puzzle for Day19Puzzle {
+    fn solve(&self, document: &str) -> Result<Answer, PuzzleError> {
+        let (workflow_chunk, part_chunk) = split_system(document)?;
+        let workflows = workflow_chunk
             .lines()
-            .map(parse_workflow)
-            .collect::<HashMap<&str, Workflow>>();
-        let parts = chunks.next().unwrap().lines().map(parse_part);
+            .map(|line| parse_workflow(line).map_err(|err| err.within(document, line)))
+            .collect::<Result<HashMap<&str, Workflow>, PuzzleError>>()?;
+        let parts = part_chunk
+            .lines()
+            .map(|line| parse_part(line).map_err(|err| err.within(document, line)))
+            .collect::<Result<Vec<Part>, PuzzleError>>()?;
 
         let mut accepted: Vec<Part> = Vec::new();
         let mut rejected: Vec<Part> = Vec::new();
         for part in parts {
-            sort_part(&workflows, part, &mut accepted, &mut rejected);
+            sort_part(&workflows, part, &mut accepted, &mut rejected)?;
         }
-        return accepted
+        return Ok(accepted
             .iter()
             .map(|part| part.score())
             .sum::<usize>()
-            .to_string();
+            .into());
     }
 
-    fn solve2(&self, document: &str) -> String {
-        let mut chunks = document.split("\n\n");
+    fn solve2(&self, document: &str) -> Result<Answer, PuzzleError> {
+        let (workflow_chunk, _) = split_system(document)?;
         let mut workflows: HashMap<&str, &str> = HashMap::new();
-        for line in chunks.next().unwrap().lines() {
-            let parts = line.split("{").collect::<Vec<&str>>();
-            workflows.insert(parts[0], parts[1].strip_suffix('}').unwrap());
+        for line in workflow_chunk.lines() {
+            parse_workflow(line).map_err(|err| err.within(document, line))?;
+            let (name, rules) = line.split_once('{').unwrap();
+            workflows.insert(name, rules.strip_suffix('}').unwrap());
         }
 
         let mut queue: Vec<(String, PartRange)> = Vec::new();
@@ -263,7 +328,13 @@ impl super::solve::Puzzle<String> for Day19Puzzle {
         let mut ans = 0_usize;
         while queue.len() > 0 {
             let (workflow, part_range) = queue.pop().unwrap();
-            let mut next_queue = apply_workflow_to_range(&workflows[workflow.as_str()], part_range);
+            let Some(rules) = workflows.get(workflow.as_str()) else {
+                return Err(PuzzleError::unsolvable(format!(
+                    "unknown workflow `{}`",
+                    workflow
+                )));
+            };
+            let mut next_queue = apply_workflow_to_range(rules, part_range);
             while next_queue.len() > 0 {
                 let (workflow, part_range) = next_queue.pop().unwrap();
                 if workflow == "A" {
@@ -273,9 +344,15 @@ impl super::solve::Puzzle<String> for Day19Puzzle {
                 }
             }
         }
-        return ans.to_string();
+        return Ok(ans.into());
     }
 }
+crate::register_puzzle!(
+    day: 19,
+    title: "Aplenty",
+    tags: ["parsing", "ranges"],
+    puzzle: Day19Puzzle {},
+);
 
 #[cfg(test)]
 mod tests {
@@ -283,16 +360,24 @@ mod tests {
 
     #[test]
     fn test_parse_part() {
-        let part = parse_part("{x=0,m=1,a=0,s=0}");
+        let part = parse_part("{x=0,m=1,a=0,s=0}").unwrap();
         assert_eq!(part.x, 0);
         assert_eq!(part.m, 1);
         assert_eq!(part.a, 0);
         assert_eq!(part.s, 0);
+        assert_eq!(
+            parse_part("{x=0,m=1,q=0,s=0}").unwrap_err().to_string(),
+            "parse error at line 1, column 10: expected rating `a=`"
+        );
     }
 
     #[test]
     fn test_parse_condition() {
-        let cond = parse_condition("x<3");
+        assert_eq!(
+            parse_condition("x=3").err().unwrap().to_string(),
+            "parse error at line 1, column 2: invalid comparer `=`"
+        );
+        let cond = parse_condition("x<3").unwrap();
         assert_eq!(
             cond(Part {
                 x: 2,
@@ -1,17 +1,19 @@
 use regex::Regex;
 
-const RED: i32 = 12;
-const GREEN: i32 = 13;
-const BLUE: i32 = 14;
+use crate::solve::{parse_token, Answer, PuzzleError};
+
+pub const RED: i32 = 12;
+pub const GREEN: i32 = 13;
+pub const BLUE: i32 = 14;
 
 #[derive(PartialEq, Debug)]
-struct BagReveal {
-    red: i32,
-    green: i32,
-    blue: i32,
+pub struct BagReveal {
+    pub red: i32,
+    pub green: i32,
+    pub blue: i32,
 }
 
-fn get_reveal(reveal_line: &str) -> BagReveal {
+pub fn get_reveal(reveal_line: &str) -> Result<BagReveal, PuzzleError> {
     let mut red: i32 = 0;
     let mut green: i32 = 0;
     let mut blue: i32 = 0;
@@ -19,8 +21,15 @@ fn get_reveal(reveal_line: &str) -> BagReveal {
     let re = Regex::new(r"([0-9]+) (blue|red|green)").unwrap();
 
     for color in reveal_line.trim().split(',') {
-        let (_, [color_value, color_name]) = re.captures(color).unwrap().extract();
-        let color_value: i32 = color_value.parse().unwrap();
+        let Some(captures) = re.captures(color) else {
+            return Err(PuzzleError::parse(
+                reveal_line,
+                color.trim(),
+                format!("invalid cube count `{}`", color.trim()),
+            ));
+        };
+        let (_, [color_value, color_name]) = captures.extract();
+        let color_value: i32 = parse_token(reveal_line, color_value)?;
 
         match color_name {
             "red" => red += color_value,
@@ -30,73 +39,84 @@ fn get_reveal(reveal_line: &str) -> BagReveal {
         }
     }
 
-    return BagReveal { red, green, blue };
+    return Ok(BagReveal { red, green, blue });
 }
 
-fn is_reveal_valid(reveal: &BagReveal) -> bool {
+pub fn is_reveal_valid(reveal: &BagReveal) -> bool {
     return reveal.red <= RED && reveal.green <= GREEN && reveal.blue <= BLUE;
 }
 
-fn is_game_valid(game_line: &str) -> bool {
-    return game_line
-        .split(":")
-        .nth(1)
-        .unwrap()
+pub fn get_game_reveals(game_line: &str) -> Result<Vec<BagReveal>, PuzzleError> {
+    let Some((_, reveals)) = game_line.split_once(':') else {
+        return Err(PuzzleError::missing(game_line, game_line, "expected `:`"));
+    };
+    return reveals
         .trim()
         .split(';')
-        .all(|reveal| {
-            let reveal = get_reveal(reveal);
-            is_reveal_valid(&reveal)
-        });
+        .map(|reveal| get_reveal(reveal).map_err(|err| err.within(game_line, reveal)))
+        .collect();
+}
+
+pub fn is_game_valid(game_line: &str) -> Result<bool, PuzzleError> {
+    return Ok(get_game_reveals(game_line)?
+        .iter()
+        .all(|reveal| is_reveal_valid(reveal)));
 }
 
-fn sum_game_ids(games: &str) -> i32 {
+pub fn sum_game_ids(games: &str) -> Result<i32, PuzzleError> {
     let re = Regex::new(r"Game ([0-9]+):").unwrap();
 
-    return games
-        .lines()
-        .filter(|line| is_game_valid(line))
-        .map(|line| {
-            re.captures(line).unwrap().extract::<1>().1[0]
-                .parse::<i32>()
-                .unwrap()
-        })
-        .sum::<i32>();
+    let mut sum: i32 = 0;
+    for line in games.lines() {
+        if !is_game_valid(line).map_err(|err| err.within(games, line))? {
+            continue;
+        }
+        let Some(captures) = re.captures(line) else {
+            return Err(PuzzleError::parse(games, line, "expected `Game <id>:`"));
+        };
+        sum += parse_token::<i32>(games, captures.extract::<1>().1[0])?;
+    }
+    return Ok(sum);
 }
 
-fn get_game_power(game_line: &str) -> i32 {
+pub fn get_game_power(game_line: &str) -> Result<i32, PuzzleError> {
     let mut red: i32 = 0;
     let mut green: i32 = 0;
     let mut blue: i32 = 0;
 
-    game_line.split(';').for_each(|reveal| {
-        let reveal = get_reveal(reveal);
+    for reveal in game_line.split(';') {
+        let reveal = get_reveal(reveal).map_err(|err| err.within(game_line, reveal))?;
         red = red.max(reveal.red);
         green = green.max(reveal.green);
         blue = blue.max(reveal.blue);
-    });
+    }
 
-    return red * green * blue;
+    return Ok(red * green * blue);
 }
 
-fn sum_game_power(games: &str) -> i32 {
-    return games
-        .lines()
-        .map(|line| line.split(':').nth(1).unwrap().trim())
-        .map(|line| get_game_power(line))
-        .sum::<i32>();
+pub fn sum_game_power(games: &str) -> Result<i32, PuzzleError> {
+    let mut sum: i32 = 0;
+    for line in games.lines() {
+        let Some((_, game)) = line.split_once(':') else {
+            return Err(PuzzleError::missing(games, line, "expected `:`"));
+        };
+        let game = game.trim();
+        sum += get_game_power(game).map_err(|err| err.within(games, game))?;
+    }
+    return Ok(sum);
 }
 
 pub struct Day2Puzzle {}
-impl super::solve::Puzzle<String> for Day2Puzzle {
-    fn solve(&self, document: &str) -> String {
-        return sum_game_ids(document).to_string();
+impl super::solve::Puzzle for Day2Puzzle {
+    fn solve(&self, document: &str) -> Result<Answer, PuzzleError> {
+        return Ok(sum_game_ids(document)?.into());
     }
 
-    fn solve2(&self, document: &str) -> String {
-        return sum_game_power(document).to_string();
+    fn solve2(&self, document: &str) -> Result<Answer, PuzzleError> {
+        return Ok(sum_game_power(document)?.into());
     }
 }
+crate::register_puzzle!(day: 2, title: "Cube Conundrum", tags: ["parsing"], puzzle: Day2Puzzle {});
 
 #[cfg(test)]
 mod tests {
@@ -106,27 +126,31 @@ mod tests {
     fn test_get_reveal() {
         assert_eq!(
             get_reveal("1 red, 1 green, 1 blue"),
-            BagReveal {
+            Ok(BagReveal {
                 red: 1,
                 green: 1,
                 blue: 1
-            }
+            })
         );
         assert_eq!(
             get_reveal("1 red"),
-            BagReveal {
+            Ok(BagReveal {
                 red: 1,
                 green: 0,
                 blue: 0
-            }
+            })
         );
         assert_eq!(
             get_reveal("10 red, 3 green"),
-            BagReveal {
+            Ok(BagReveal {
                 red: 10,
                 green: 3,
                 blue: 0
-            }
+            })
+        );
+        assert_eq!(
+            get_reveal("10 red, 3 purple").unwrap_err().to_string(),
+            "parse error at line 1, column 9: invalid cube count `3 purple`"
         );
     }
 
@@ -152,32 +176,34 @@ mod tests {
 
     #[test]
     fn test_is_game_valid() {
-        assert!(is_game_valid(
-            "Game 1: 1 red, 1 green, 1 blue; 1 red, 1 blue"
-        ));
-        assert!(!is_game_valid(
-            "Game 2: 1 red, 1 green, 1 blue; 20 red, 1 green"
-        ));
+        assert!(is_game_valid("Game 1: 1 red, 1 green, 1 blue; 1 red, 1 blue").unwrap());
+        assert!(!is_game_valid("Game 2: 1 red, 1 green, 1 blue; 20 red, 1 green").unwrap());
     }
 
     #[test]
     fn test_sum_game_ids() {
         let document: &str = "Game 1: 1 red, 1 green, 1 blue; 1 red, 1 blue\nGame 2: 1 red, 1 green, 1 blue; 20 red, 1 green";
-        assert_eq!(sum_game_ids(document), 1);
+        assert_eq!(sum_game_ids(document), Ok(1));
 
         let document: &str = "Game 1: 1 red, 1 green, 1 blue; 1 red, 1 blue\nGame 2: 1 red, 1 green, 1 blue; 1 red, 1 blue";
-        assert_eq!(sum_game_ids(document), 3);
+        assert_eq!(sum_game_ids(document), Ok(3));
+
+        let document: &str = "Game 1: 1 red\nGame 2: 1 red; 1 green, blue";
+        assert_eq!(
+            sum_game_ids(document).unwrap_err().to_string(),
+            "parse error at line 2, column 25: invalid cube count `blue`"
+        );
     }
 
     #[test]
     fn test_get_game_power() {
         assert_eq!(
             get_game_power("1 red, 1 green, 1 blue; 1 red, 1 blue"),
-            1 * 1 * 1
+            Ok(1 * 1 * 1)
         );
         assert_eq!(
             get_game_power("1 red, 3 green, 1 blue; 20 red, 1 green"),
-            20 * 3 * 1
+            Ok(20 * 3 * 1)
         );
     }
 }
@@ -2,6 +2,8 @@ use std::collections::hash_map::DefaultHasher;
 use std::collections::{BTreeMap, HashMap, HashSet};
 use std::hash::{Hash, Hasher};
 
+use crate::solve::{Answer, PuzzleError};
+
 #[derive(Debug, Hash, PartialEq, Eq, Clone, Copy)]
 pub enum Signal {
     LOW,
@@ -17,8 +19,8 @@ impl Signal {
 }
 
 #[derive(Debug, Hash, PartialEq, Eq)]
-struct Conjunction {
-    state: BTreeMap<String, Signal>,
+pub struct Conjunction {
+    pub state: BTreeMap<String, Signal>,
 }
 impl ComModule for Conjunction {
     fn output(&mut self, signal: Signal, origin: &str) -> Option<Signal> {
@@ -42,8 +44,8 @@ impl ComModule for Conjunction {
     }
 }
 #[derive(Debug, Hash, PartialEq, Eq)]
-struct FlipFlop {
-    state: Signal,
+pub struct FlipFlop {
+    pub state: Signal,
 }
 impl ComModule for FlipFlop {
     fn output(&mut self, signal: Signal, _origin: &str) -> Option<Signal> {
@@ -61,7 +63,7 @@ impl ComModule for FlipFlop {
     }
 }
 #[derive(Debug, Hash, PartialEq, Eq)]
-struct Broadcast {}
+pub struct Broadcast {}
 impl ComModule for Broadcast {
     fn output(&mut self, signal: Signal, _origin: &str) -> Option<Signal> {
         return Some(signal);
@@ -72,16 +74,16 @@ impl ComModule for Broadcast {
     }
 }
 
-trait ComModule {
+pub trait ComModule {
     fn output(&mut self, signal: Signal, origin: &str) -> Option<Signal>;
     fn reset(&mut self, _input_nodes: Vec<String>) {}
     fn my_hash(&self, state: &mut DefaultHasher);
     fn mod_type(&self) -> &str;
 }
 
-struct ComModuleNetwork {
-    modules: BTreeMap<String, Box<dyn ComModule>>,
-    outputs: BTreeMap<String, Vec<String>>,
+pub struct ComModuleNetwork {
+    pub modules: BTreeMap<String, Box<dyn ComModule>>,
+    pub outputs: BTreeMap<String, Vec<String>>,
 }
 impl ComModuleNetwork {
     fn calculate_hash(&self) -> u64 {
@@ -94,38 +96,47 @@ impl ComModuleNetwork {
     }
 }
 
-fn load_module(line: &str) -> (String, Box<dyn ComModule>, Vec<String>) {
-    let parts = line.split(" -> ").collect::<Vec<&str>>();
-    let outputs = parts[1]
+pub fn load_module(line: &str) -> Result<(String, Box<dyn ComModule>, Vec<String>), PuzzleError> {
+    let Some((module, outputs)) = line.split_once(" -> ") else {
+        return Err(PuzzleError::missing(line, line, "expected `->`"));
+    };
+    let outputs = outputs
         .split(", ")
         .map(|x| x.to_string())
         .collect::<Vec<String>>();
-    if parts[0] == "broadcaster" {
-        return ("broadcaster".to_string(), Box::new(Broadcast {}), outputs);
+    if module == "broadcaster" {
+        return Ok(("broadcaster".to_string(), Box::new(Broadcast {}), outputs));
     }
-    let mod_type: &str = &parts[0][0..1];
-    let mod_name = parts[0][1..].to_string();
 
-    return match mod_type {
-        "&" => (
-            mod_name,
+    return match module.chars().next() {
+        Some('&') => Ok((
+            module[1..].to_string(),
             Box::new(Conjunction {
                 state: BTreeMap::new(),
             }),
             outputs,
-        ),
-        "%" => (mod_name, Box::new(FlipFlop { state: Signal::LOW }), outputs),
-        _ => panic!("Invalid module type"),
+        )),
+        Some('%') => Ok((
+            module[1..].to_string(),
+            Box::new(FlipFlop { state: Signal::LOW }),
+            outputs,
+        )),
+        _ => Err(PuzzleError::parse(
+            line,
+            module,
+            format!("invalid module `{}`", module),
+        )),
     };
 }
 
-fn load_all_modules(document: &str) -> ComModuleNetwork {
+pub fn load_all_modules(document: &str) -> Result<ComModuleNetwork, PuzzleError> {
     let mut modules: BTreeMap<String, Box<dyn ComModule>> = BTreeMap::new();
     let mut node_outputs: BTreeMap<String, Vec<String>> = BTreeMap::new();
     let mut node_inputs: BTreeMap<String, Vec<String>> = BTreeMap::new();
 
     for line in document.lines() {
-        let (name, module, outputs) = load_module(line);
+        let (name, module, outputs) =
+            load_module(line).map_err(|err| err.within(document, line))?;
         modules.insert(name.clone(), module);
         for output in &outputs {
             if !node_inputs.contains_key(output) {
@@ -139,13 +150,17 @@ fn load_all_modules(document: &str) -> ComModuleNetwork {
         module.reset(node_inputs.get(name).unwrap_or(&vec![]).clone());
     }
 
-    return ComModuleNetwork {
+    if !modules.contains_key("broadcaster") {
+        return Err(PuzzleError::unsolvable("no `broadcaster` module"));
+    }
+
+    return Ok(ComModuleNetwork {
         modules,
         outputs: node_outputs,
-    };
+    });
 }
 
-fn run_network(network: &mut ComModuleNetwork) -> (usize, usize) {
+pub fn run_network(network: &mut ComModuleNetwork) -> (usize, usize) {
     let mut queue: Vec<(String, String, Signal)> = Vec::new();
     queue.push(("broadcaster".to_string(), "".to_string(), Signal::LOW));
 
@@ -180,14 +195,14 @@ fn run_network(network: &mut ComModuleNetwork) -> (usize, usize) {
     return (high_count, low_count);
 }
 
-fn sum_tuples(a: Vec<(usize, usize)>) -> (usize, usize) {
+pub fn sum_tuples(a: Vec<(usize, usize)>) -> (usize, usize) {
     return a.iter().fold((0, 0), |(a, b), (c, d)| (a + c, b + d));
 }
 
 pub struct Day20Puzzle {}
-impl super::solve::Puzzle<String> for Day20Puzzle {
-    fn solve(&self, document: &str) -> String {
-        let mut network = load_all_modules(document);
+impl super::solve::Puzzle for Day20Puzzle {
+    fn solve(&self, document: &str) -> Result<Answer, PuzzleError> {
+        let mut network = load_all_modules(document)?;
         let mut states: HashMap<u64, (usize, (usize, usize))> = HashMap::new();
 
         let mut iters = 0;
@@ -210,7 +225,7 @@ impl super::solve::Puzzle<String> for Day20Puzzle {
         }
         if iters == 1000 {
             let (high_count, low_count) = sum_tuples(seq_states);
-            return (high_count as u128 * low_count as u128).to_string();
+            return Ok((high_count as u128 * low_count as u128).into());
         }
         let dif = iters - states.get(&network.calculate_hash()).unwrap().0;
         let start_loop = iters - dif;
@@ -226,13 +241,13 @@ impl super::solve::Puzzle<String> for Day20Puzzle {
         let high_count = ihs + reps * lhs + rhs;
         let low_count = ils + reps * lls + rls;
 
-        return (high_count as u128 * low_count as u128).to_string();
+        return Ok((high_count as u128 * low_count as u128).into());
     }
 
-    fn solve2(&self, document: &str) -> String {
+    fn solve2(&self, document: &str) -> Result<Answer, PuzzleError> {
         // Hard to solve in general. Let's visualize the problem
         // Credit to the subreddit
-        let network = load_all_modules(document);
+        let network = load_all_modules(document)?;
         let branch_starts = network.outputs.get("broadcaster").unwrap();
 
         let flipflops = network
@@ -245,53 +260,54 @@ impl super::solve::Puzzle<String> for Day20Puzzle {
         let mut key_values: Vec<u128> = Vec::new();
 
         for branch_start in branch_starts {
-            let mut path: Vec<String> = vec![];
             let mut active = branch_start.clone();
-            let mut targets: HashSet<String> = HashSet::new();
 
             let mut key_value = 0_usize;
             let mut inc = 1_usize;
             loop {
-                let outputs = network.outputs.get(&active).unwrap();
+                let Some(outputs) = network.outputs.get(&active) else {
+                    return Err(PuzzleError::unsupported(format!(
+                        "broadcaster output `{}` is not a module",
+                        active
+                    )));
+                };
                 let flipflouts = outputs
                     .iter()
                     .filter(|x| flipflops.contains(*x))
                     .collect::<Vec<&String>>();
-                let non_flipflouts = outputs
-                    .iter()
-                    .filter(|x| !flipflops.contains(*x))
-                    .collect::<Vec<&String>>();
 
                 let is_key: bool = match outputs.len() - flipflouts.len() {
                     0 => false,
                     1 => true,
-                    _ => panic!("Invalid number of outputs"),
+                    _ => {
+                        return Err(PuzzleError::unsupported(format!(
+                            "module `{}` feeds more than one conjunction",
+                            active
+                        )))
+                    }
                 };
-                let key_char = match is_key {
-                    true => '*',
-                    false => ' ',
-                };
-
                 if is_key {
                     key_value += inc;
                 }
                 inc <<= 1;
 
-                path.push(format!("{}{}", active, key_char));
                 if flipflouts.len() == 0 {
                     break;
                 }
                 active = flipflouts[0].clone();
-
-                targets.extend(non_flipflouts.iter().map(|x| x.to_string()));
             }
-            println!("{:?} {:?} {}", path, targets, key_value);
             key_values.push(key_value as u128);
         }
 
-        return key_values.iter().fold(1_u128, |a, b| a * b).to_string();
+        return Ok(key_values.iter().fold(1_u128, |a, b| a * b).into());
     }
 }
+crate::register_puzzle!(
+    day: 20,
+    title: "Pulse Propagation",
+    tags: ["simulation", "cycles", "math"],
+    puzzle: Day20Puzzle {},
+);
 
 #[cfg(test)]
 mod tests {
@@ -299,7 +315,7 @@ mod tests {
 
     #[test]
     fn test_load_conjunction_module() {
-        let (name, mut action, outputs) = load_module("&a -> b");
+        let (name, mut action, outputs) = load_module("&a -> b").unwrap();
         assert_eq!(name, "a".to_string());
         assert_eq!(outputs, vec!["b".to_string()]);
         action.output(Signal::LOW, "c");
@@ -311,11 +327,21 @@ mod tests {
 
     #[test]
     fn test_load_flip_flop_module() {
-        let (name, mut action, outputs) = load_module("%a -> b, c");
+        let (name, mut action, outputs) = load_module("%a -> b, c").unwrap();
         assert_eq!(name, "a".to_string());
         assert_eq!(outputs, vec!["b".to_string(), "c".to_string()]);
         assert_eq!(action.output(Signal::HIGH, "c"), None);
         assert_eq!(action.output(Signal::LOW, "c"), Some(Signal::HIGH));
         assert_eq!(action.output(Signal::LOW, "c"), Some(Signal::LOW));
     }
+    #[test]
+    fn test_load_invalid_module() {
+        assert_eq!(
+            load_all_modules("broadcaster -> a\n#a -> b")
+                .err()
+                .unwrap()
+                .to_string(),
+            "parse error at line 2, column 1: invalid module `#a`"
+        );
+    }
 }
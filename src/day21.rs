@@ -0,0 +1,267 @@
+use std::collections::HashSet;
+
+use crate::solve::{check_chars, grid_size, Answer, PuzzleError};
+
+/// Steps the elf takes in part 1 of the real puzzle.
+pub const STEPS: usize = 64;
+/// Steps the elf takes on the infinite garden in part 2 of the real puzzle.
+pub const INFINITE_STEPS: usize = 26501365;
+/// How many times in a row the second difference of the counts must repeat before part 2 trusts
+/// it to stay constant.
+const STABLE_ROUNDS: usize = 3;
+
+pub struct Garden {
+    pub height: i64,
+    pub width: i64,
+    rocks: Vec<Vec<bool>>,
+    pub start: (i64, i64),
+}
+impl Garden {
+    pub fn parse(document: &str) -> Result<Garden, PuzzleError> {
+        let (height, width) = grid_size(document)?;
+        check_chars(document, ".#S")?;
+        let mut start = None;
+        for (row, line) in document.lines().enumerate() {
+            for (col, (i, c)) in line.char_indices().enumerate() {
+                if c != 'S' {
+                    continue;
+                }
+                if start.is_some() {
+                    return Err(PuzzleError::parse(
+                        document,
+                        &line[i..],
+                        "more than one starting tile `S`",
+                    ));
+                }
+                start = Some((row as i64, col as i64));
+            }
+        }
+        let Some(start) = start else {
+            return Err(PuzzleError::unsolvable("no starting tile `S`"));
+        };
+        return Ok(Garden {
+            height: height as i64,
+            width: width as i64,
+            rocks: document
+                .lines()
+                .map(|line| line.chars().map(|c| c == '#').collect())
+                .collect(),
+            start,
+        });
+    }
+
+    /// Whether the tile at `pos` is a garden plot. Off the map the garden repeats if `tiled`,
+    /// and there are no plots otherwise.
+    pub fn is_plot(&self, pos: (i64, i64), tiled: bool) -> bool {
+        let (row, col) = pos;
+        if !tiled && (row < 0 || row >= self.height || col < 0 || col >= self.width) {
+            return false;
+        }
+        let row = row.rem_euclid(self.height) as usize;
+        let col = col.rem_euclid(self.width) as usize;
+        return !self.rocks[row][col];
+    }
+}
+
+/// A breadth-first walk from the start of a garden, taken one step at a time as more steps are
+/// asked for.
+pub struct Walk<'a> {
+    garden: &'a Garden,
+    tiled: bool,
+    /// Plots first reached one step before the frontier. The garden is a checkerboard, so these
+    /// are the only visited plots a step from the frontier can lead back to.
+    previous: HashSet<(i64, i64)>,
+    frontier: HashSet<(i64, i64)>,
+    /// `reachable[n]` is the number of plots reachable in exactly `n` steps.
+    reachable: Vec<u64>,
+}
+impl<'a> Walk<'a> {
+    pub fn new(garden: &'a Garden, tiled: bool) -> Walk<'a> {
+        return Walk {
+            garden,
+            tiled,
+            previous: HashSet::new(),
+            frontier: HashSet::from([garden.start]),
+            reachable: vec![1],
+        };
+    }
+
+    fn step(&mut self) {
+        let mut next: HashSet<(i64, i64)> = HashSet::new();
+        for &(row, col) in self.frontier.iter() {
+            for pos in [
+                (row - 1, col),
+                (row + 1, col),
+                (row, col - 1),
+                (row, col + 1),
+            ] {
+                if self.garden.is_plot(pos, self.tiled) && !self.previous.contains(&pos) {
+                    next.insert(pos);
+                }
+            }
+        }
+        // Any plot reached in n - 2 steps can be reached in n steps by stepping back and forth.
+        let steps = self.reachable.len();
+        let before = match steps {
+            1 => 0,
+            _ => self.reachable[steps - 2],
+        };
+        self.reachable.push(before + next.len() as u64);
+        self.previous = std::mem::replace(&mut self.frontier, next);
+    }
+
+    /// The number of plots reachable in exactly `steps` steps.
+    pub fn reachable(&mut self, steps: usize) -> u64 {
+        while self.reachable.len() <= steps && !self.frontier.is_empty() {
+            self.step();
+        }
+        if steps < self.reachable.len() {
+            return self.reachable[steps];
+        }
+        // The walk has covered everything it can, so the count only alternates from here.
+        let last = self.reachable.len() - 1;
+        return self.reachable[last - (steps - last) % 2];
+    }
+}
+
+/// The number of plots reachable in exactly `steps` steps on the infinite garden.
+///
+/// Once the walk has spread over a few copies of the map, every further map width adds a ring
+/// of copies that grows by the same amount each time, so the counts after `steps` modulo the
+/// width plus a whole number of widths grow quadratically. They are walked until their second
+/// difference has settled, and extrapolated from there.
+pub fn count_infinite_plots(garden: &Garden, steps: usize) -> Result<u64, PuzzleError> {
+    if garden.height != garden.width {
+        return Err(PuzzleError::unsupported(format!(
+            "the garden is {}x{} instead of square",
+            garden.height, garden.width
+        )));
+    }
+    let period = garden.width as usize;
+    let mut walk = Walk::new(garden, true);
+    let mut counts: Vec<i64> = Vec::new();
+    let mut stable = 0;
+    let mut n = steps % period;
+    loop {
+        counts.push(walk.reachable(n) as i64);
+        if n == steps {
+            return Ok(walk.reachable(n));
+        }
+        n += period;
+        let k = counts.len() - 1;
+        if k < 3 {
+            continue;
+        }
+        let second_difference = |i: usize| counts[i] - 2 * counts[i - 1] + counts[i - 2];
+        match second_difference(k) == second_difference(k - 1) {
+            true => stable += 1,
+            false => stable = 0,
+        }
+        if stable == STABLE_ROUNDS {
+            let remaining = ((steps - n) / period + 1) as i64;
+            let difference = counts[k] - counts[k - 1];
+            return Ok((counts[k]
+                + remaining * difference
+                + second_difference(k) * remaining * (remaining + 1) / 2)
+                as u64);
+        }
+    }
+}
+
+pub struct Day21Puzzle {
+    /// Steps the elf takes in part 1.
+    pub steps: usize,
+    /// Steps the elf takes on the infinite garden in part 2.
+    pub infinite_steps: usize,
+}
+impl super::solve::Puzzle for Day21Puzzle {
+    fn solve(&self, document: &str) -> Result<Answer, PuzzleError> {
+        let garden = Garden::parse(document)?;
+        return Ok(Walk::new(&garden, false).reachable(self.steps).into());
+    }
+
+    fn solve2(&self, document: &str) -> Result<Answer, PuzzleError> {
+        let garden = Garden::parse(document)?;
+        return Ok(count_infinite_plots(&garden, self.infinite_steps)?.into());
+    }
+}
+crate::register_puzzle!(
+    day: 21,
+    title: "Step Counter",
+    tags: ["grid", "bfs", "extrapolation"],
+    puzzle: Day21Puzzle {
+        steps: STEPS,
+        infinite_steps: INFINITE_STEPS,
+    },
+);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solve::Puzzle;
+
+    const EXAMPLE: &str = "...........
+.....###.#.
+.###.##..#.
+..#.#...#..
+....#.#....
+.##..S####.
+.##..#...#.
+.......##..
+.##.#.####.
+.##..##.##.
+...........";
+
+    #[test]
+    fn test_parse() {
+        let garden = Garden::parse(EXAMPLE).unwrap();
+        assert_eq!((garden.height, garden.width), (11, 11));
+        assert_eq!(garden.start, (5, 5));
+        assert!(garden.is_plot((0, 0), false));
+        assert!(!garden.is_plot((1, 5), false));
+        assert!(!garden.is_plot((-1, 0), false));
+        assert!(garden.is_plot((-1, 0), true));
+        assert!(!garden.is_plot((12, 16), true));
+        assert_eq!(
+            Garden::parse("S.\n.S").err().unwrap().to_string(),
+            "parse error at line 2, column 2: more than one starting tile `S`"
+        );
+        assert!(Garden::parse("..\n.#").is_err());
+    }
+
+    #[test]
+    fn test_walk() {
+        let garden = Garden::parse(EXAMPLE).unwrap();
+        let mut walk = Walk::new(&garden, false);
+        assert_eq!(walk.reachable(0), 1);
+        assert_eq!(walk.reachable(1), 2);
+        assert_eq!(walk.reachable(2), 4);
+        assert_eq!(walk.reachable(3), 6);
+        assert_eq!(walk.reachable(6), 16);
+
+        let mut walk = Walk::new(&garden, true);
+        assert_eq!(walk.reachable(10), 50);
+        assert_eq!(walk.reachable(50), 1594);
+        assert_eq!(walk.reachable(100), 6536);
+    }
+
+    #[test]
+    fn test_count_infinite_plots() {
+        let garden = Garden::parse(EXAMPLE).unwrap();
+        assert_eq!(count_infinite_plots(&garden, 6).unwrap(), 16);
+        assert_eq!(count_infinite_plots(&garden, 100).unwrap(), 6536);
+        assert_eq!(count_infinite_plots(&garden, 500).unwrap(), 167004);
+        assert_eq!(count_infinite_plots(&garden, 1000).unwrap(), 668697);
+        assert_eq!(count_infinite_plots(&garden, 5000).unwrap(), 16733044);
+    }
+
+    #[test]
+    fn test_solve() {
+        let puzzle = Day21Puzzle {
+            steps: 6,
+            infinite_steps: 1000,
+        };
+        assert_eq!(puzzle.solve(EXAMPLE).unwrap(), Answer::from(16));
+        assert_eq!(puzzle.solve2(EXAMPLE).unwrap(), Answer::from(668697));
+    }
+}
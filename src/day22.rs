@@ -0,0 +1,246 @@
+use std::collections::{HashMap, HashSet, VecDeque};
+
+use crate::solve::{parse_token, Answer, PuzzleError};
+
+/// A brick spanning every cube from `min` to `max`, both included, as `(x, y, z)`.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct Brick {
+    pub min: (i32, i32, i32),
+    pub max: (i32, i32, i32),
+}
+impl Brick {
+    /// The `(x, y)` columns the brick covers.
+    pub fn footprint(&self) -> impl Iterator<Item = (i32, i32)> + '_ {
+        return (self.min.0..=self.max.0)
+            .flat_map(move |x| (self.min.1..=self.max.1).map(move |y| (x, y)));
+    }
+
+    /// The brick moved `dz` cubes down.
+    pub fn lowered(&self, dz: i32) -> Brick {
+        return Brick {
+            min: (self.min.0, self.min.1, self.min.2 - dz),
+            max: (self.max.0, self.max.1, self.max.2 - dz),
+        };
+    }
+}
+
+fn parse_point(document: &str, text: &str) -> Result<(i32, i32, i32), PuzzleError> {
+    let coords = text
+        .split(',')
+        .map(|coord| parse_token::<i32>(document, coord))
+        .collect::<Result<Vec<i32>, PuzzleError>>()?;
+    let [x, y, z] = coords[..] else {
+        return Err(PuzzleError::parse(
+            document,
+            text,
+            format!("expected 3 coordinates, found {}", coords.len()),
+        ));
+    };
+    return Ok((x, y, z));
+}
+
+/// Parses one brick per line, written as the coordinates of its two ends: `x,y,z~x,y,z`.
+pub fn get_bricks(document: &str) -> Result<Vec<Brick>, PuzzleError> {
+    return document
+        .lines()
+        .map(|line| {
+            let Some((start, end)) = line.split_once('~') else {
+                return Err(PuzzleError::missing(document, line, "missing `~`"));
+            };
+            let start = parse_point(document, start)?;
+            let end = parse_point(document, end)?;
+            let brick = Brick {
+                min: (start.0.min(end.0), start.1.min(end.1), start.2.min(end.2)),
+                max: (start.0.max(end.0), start.1.max(end.1), start.2.max(end.2)),
+            };
+            if brick.min.2 < 1 {
+                return Err(PuzzleError::parse(document, line, "brick below the ground"));
+            }
+            return Ok(brick);
+        })
+        .collect();
+}
+
+/// Bricks at rest after everything has fallen as far as it can, in the same order as they were
+/// given.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Layout {
+    pub bricks: Vec<Brick>,
+}
+impl Layout {
+    /// Lets the bricks fall, lowest first, until each lies on the ground at `z = 1` or on
+    /// another brick.
+    pub fn settle(bricks: &[Brick]) -> Layout {
+        let mut order: Vec<usize> = (0..bricks.len()).collect();
+        order.sort_by_key(|&i| bricks[i].min.2);
+
+        let mut heights: HashMap<(i32, i32), i32> = HashMap::new();
+        let mut settled = bricks.to_vec();
+        for i in order {
+            let floor = bricks[i]
+                .footprint()
+                .map(|column| heights.get(&column).copied().unwrap_or(0))
+                .max()
+                .unwrap();
+            settled[i] = bricks[i].lowered(bricks[i].min.2 - floor - 1);
+            for column in settled[i].footprint() {
+                heights.insert(column, settled[i].max.2);
+            }
+        }
+        return Layout { bricks: settled };
+    }
+}
+
+/// Which bricks of a [`Layout`] rest on which, by their index in the layout.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct SupportGraph {
+    /// `supports[i]`: the bricks resting directly on brick `i`.
+    pub supports: Vec<Vec<usize>>,
+    /// `supported_by[i]`: the bricks brick `i` rests directly on. Empty for bricks on the ground.
+    pub supported_by: Vec<Vec<usize>>,
+}
+impl SupportGraph {
+    pub fn new(layout: &Layout) -> SupportGraph {
+        let mut tops: HashMap<(i32, i32, i32), usize> = HashMap::new();
+        for (i, brick) in layout.bricks.iter().enumerate() {
+            for (x, y) in brick.footprint() {
+                tops.insert((x, y, brick.max.2), i);
+            }
+        }
+
+        let mut supports = vec![Vec::new(); layout.bricks.len()];
+        let mut supported_by = vec![Vec::new(); layout.bricks.len()];
+        for (i, brick) in layout.bricks.iter().enumerate() {
+            let below: HashSet<usize> = brick
+                .footprint()
+                .filter_map(|(x, y)| tops.get(&(x, y, brick.min.2 - 1)).copied())
+                .collect();
+            for j in below {
+                supports[j].push(i);
+                supported_by[i].push(j);
+            }
+        }
+        for list in supports.iter_mut().chain(supported_by.iter_mut()) {
+            list.sort_unstable();
+        }
+        return SupportGraph {
+            supports,
+            supported_by,
+        };
+    }
+
+    /// Whether brick `i` can be removed without any other brick falling, because every brick
+    /// resting on it also rests on another one.
+    pub fn is_safe(&self, i: usize) -> bool {
+        return self.supports[i]
+            .iter()
+            .all(|&j| self.supported_by[j].len() > 1);
+    }
+
+    /// The number of other bricks that fall if brick `i` is removed.
+    pub fn chain_reaction(&self, i: usize) -> usize {
+        let mut remaining: Vec<usize> = self.supported_by.iter().map(Vec::len).collect();
+        let mut queue = VecDeque::from([i]);
+        let mut fallen = 0;
+        while let Some(brick) = queue.pop_front() {
+            for &above in self.supports[brick].iter() {
+                remaining[above] -= 1;
+                if remaining[above] == 0 {
+                    fallen += 1;
+                    queue.push_back(above);
+                }
+            }
+        }
+        return fallen;
+    }
+}
+
+pub struct Day22Puzzle {}
+impl super::solve::Puzzle for Day22Puzzle {
+    fn solve(&self, document: &str) -> Result<Answer, PuzzleError> {
+        let layout = Layout::settle(&get_bricks(document)?);
+        let graph = SupportGraph::new(&layout);
+        let safe = (0..layout.bricks.len())
+            .filter(|&i| graph.is_safe(i))
+            .count();
+        return Ok(safe.into());
+    }
+
+    fn solve2(&self, document: &str) -> Result<Answer, PuzzleError> {
+        let layout = Layout::settle(&get_bricks(document)?);
+        let graph = SupportGraph::new(&layout);
+        let fallen: usize = (0..layout.bricks.len())
+            .map(|i| graph.chain_reaction(i))
+            .sum();
+        return Ok(fallen.into());
+    }
+}
+crate::register_puzzle!(
+    day: 22,
+    title: "Sand Slabs",
+    tags: ["parsing", "simulation", "graph"],
+    puzzle: Day22Puzzle {},
+);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "1,0,1~1,2,1
+0,0,2~2,0,2
+0,2,3~2,2,3
+0,0,4~0,2,4
+2,0,5~2,2,5
+0,1,6~2,1,6
+1,1,8~1,1,9";
+
+    #[test]
+    fn test_get_bricks() {
+        let bricks = get_bricks("1,0,1~1,2,1\n2,2,5~2,0,5").unwrap();
+        assert_eq!(
+            bricks,
+            vec![
+                Brick {
+                    min: (1, 0, 1),
+                    max: (1, 2, 1)
+                },
+                Brick {
+                    min: (2, 0, 5),
+                    max: (2, 2, 5)
+                },
+            ]
+        );
+        assert_eq!(
+            get_bricks("1,0,1~1,2,1\n1,0,1-1,2,1")
+                .unwrap_err()
+                .to_string(),
+            "parse error at line 2, column 12: missing `~`"
+        );
+        assert_eq!(
+            get_bricks("1,0~1,2,1").unwrap_err().to_string(),
+            "parse error at line 1, column 1: expected 3 coordinates, found 2"
+        );
+        assert!(get_bricks("1,0,0~1,2,1").is_err());
+    }
+
+    #[test]
+    fn test_settle() {
+        let layout = Layout::settle(&get_bricks(EXAMPLE).unwrap());
+        let bottoms: Vec<i32> = layout.bricks.iter().map(|brick| brick.min.2).collect();
+        assert_eq!(bottoms, vec![1, 2, 2, 3, 3, 4, 5]);
+        assert_eq!(layout.bricks[6].max, (1, 1, 6));
+    }
+
+    #[test]
+    fn test_support_graph() {
+        let graph = SupportGraph::new(&Layout::settle(&get_bricks(EXAMPLE).unwrap()));
+        assert_eq!(graph.supports[0], vec![1, 2]);
+        assert_eq!(graph.supported_by[3], vec![1, 2]);
+        assert_eq!(graph.supported_by[0], Vec::<usize>::new());
+        let safe: Vec<usize> = (0..7).filter(|&i| graph.is_safe(i)).collect();
+        assert_eq!(safe, vec![1, 2, 3, 4, 6]);
+        assert_eq!(graph.chain_reaction(0), 6);
+        assert_eq!(graph.chain_reaction(5), 1);
+        assert_eq!(graph.chain_reaction(1), 0);
+    }
+}
@@ -0,0 +1,242 @@
+use std::collections::HashMap;
+
+use crate::solve::{check_chars, grid_size, Answer, PuzzleError};
+
+const DIRECTIONS: [(isize, isize, u8); 4] =
+    [(-1, 0, b'^'), (0, 1, b'>'), (1, 0, b'v'), (0, -1, b'<')];
+
+pub struct TrailMap {
+    grid: Vec<Vec<u8>>,
+    pub start: (usize, usize),
+    pub end: (usize, usize),
+}
+impl TrailMap {
+    pub fn parse(document: &str) -> Result<TrailMap, PuzzleError> {
+        grid_size(document)?;
+        check_chars(document, "#.^>v<")?;
+        let grid: Vec<Vec<u8>> = document
+            .lines()
+            .map(|line| line.bytes().collect())
+            .collect();
+        let opening = |row: usize| grid[row].iter().position(|&c| c == b'.');
+        let Some(start) = opening(0) else {
+            return Err(PuzzleError::unsolvable("no path in the top row"));
+        };
+        let Some(end) = opening(grid.len() - 1) else {
+            return Err(PuzzleError::unsolvable("no path in the bottom row"));
+        };
+        let end = (grid.len() - 1, end);
+        return Ok(TrailMap {
+            grid,
+            start: (0, start),
+            end,
+        });
+    }
+
+    /// The tile one step from `pos` in direction `(dr, dc)`, if it is on the map and not forest.
+    fn step(&self, pos: (usize, usize), dr: isize, dc: isize) -> Option<(usize, usize)> {
+        let row = pos.0.checked_add_signed(dr)?;
+        let col = pos.1.checked_add_signed(dc)?;
+        return match self.grid.get(row)?.get(col)? {
+            b'#' => None,
+            _ => Some((row, col)),
+        };
+    }
+
+    /// The tiles one step away from `pos`. On `slippery` slopes, that is only the tile downhill.
+    fn neighbors(&self, pos: (usize, usize), slippery: bool) -> Vec<(usize, usize)> {
+        let tile = self.grid[pos.0][pos.1];
+        return DIRECTIONS
+            .iter()
+            .filter(|&&(_, _, slope)| !slippery || tile == b'.' || tile == slope)
+            .filter_map(|&(dr, dc, _)| self.step(pos, dr, dc))
+            .collect();
+    }
+
+    /// The start, the end, and every tile where the trail forks, in that order.
+    pub fn junctions(&self) -> Vec<(usize, usize)> {
+        let mut junctions = vec![self.start, self.end];
+        for (row, line) in self.grid.iter().enumerate() {
+            for (col, &tile) in line.iter().enumerate() {
+                let pos = (row, col);
+                if pos == self.start || pos == self.end {
+                    continue;
+                }
+                if tile != b'#' && self.neighbors(pos, false).len() > 2 {
+                    junctions.push(pos);
+                }
+            }
+        }
+        return junctions;
+    }
+}
+
+/// The trail map reduced to its junctions, with an edge for every stretch of trail between two
+/// of them. Junction 0 is the start and junction 1 the end.
+pub struct TrailGraph {
+    pub junctions: Vec<(usize, usize)>,
+    /// `edges[i]`: the junctions reachable from junction `i` without passing another one, with
+    /// the number of steps it takes.
+    pub edges: Vec<Vec<(usize, usize)>>,
+}
+impl TrailGraph {
+    /// Follows each trail leaving each junction to the next junction. With `slippery` slopes,
+    /// trails that would have to climb a slope are left out.
+    pub fn new(map: &TrailMap, slippery: bool) -> Result<TrailGraph, PuzzleError> {
+        let junctions = map.junctions();
+        if junctions.len() > 64 {
+            return Err(PuzzleError::unsupported(format!(
+                "{} junctions, but at most 64 are supported",
+                junctions.len()
+            )));
+        }
+        let index: HashMap<(usize, usize), usize> = junctions
+            .iter()
+            .enumerate()
+            .map(|(i, &pos)| (pos, i))
+            .collect();
+
+        let mut edges = vec![Vec::new(); junctions.len()];
+        for (i, &junction) in junctions.iter().enumerate() {
+            for first in map.neighbors(junction, slippery) {
+                let (mut previous, mut current) = (junction, first);
+                let mut length = 1;
+                while !index.contains_key(&current) {
+                    let next = map
+                        .neighbors(current, slippery)
+                        .into_iter()
+                        .find(|&pos| pos != previous);
+                    let Some(next) = next else {
+                        break;
+                    };
+                    (previous, current) = (current, next);
+                    length += 1;
+                }
+                if let Some(&j) = index.get(&current) {
+                    edges[i].push((j, length));
+                }
+            }
+        }
+        return Ok(TrailGraph { junctions, edges });
+    }
+
+    /// The number of steps of the longest hike from the start to the end that never visits a
+    /// junction twice, or `None` if the end cannot be reached.
+    pub fn longest_hike(&self) -> Option<usize> {
+        // Reaching the only junction next to the end leaves no choice but to go there, so the
+        // search can stop at that junction instead of wandering elsewhere first.
+        let mut goal = (1, 0);
+        let into_end: Vec<usize> = (0..self.junctions.len())
+            .filter(|&i| self.edges[i].iter().any(|&(j, _)| j == 1))
+            .collect();
+        if let [last] = into_end[..] {
+            let length = self.edges[last]
+                .iter()
+                .filter(|&&(j, _)| j == 1)
+                .map(|&(_, length)| length)
+                .max()
+                .unwrap();
+            goal = (last, length);
+        }
+        return self.search(0, 1 << 0, goal);
+    }
+
+    fn search(&self, junction: usize, visited: u64, goal: (usize, usize)) -> Option<usize> {
+        if junction == goal.0 {
+            return Some(goal.1);
+        }
+        return self.edges[junction]
+            .iter()
+            .filter(|&&(next, _)| visited & (1 << next) == 0)
+            .filter_map(|&(next, length)| {
+                Some(length + self.search(next, visited | (1 << next), goal)?)
+            })
+            .max();
+    }
+}
+
+fn get_longest_hike(document: &str, slippery: bool) -> Result<usize, PuzzleError> {
+    let graph = TrailGraph::new(&TrailMap::parse(document)?, slippery)?;
+    return graph
+        .longest_hike()
+        .ok_or_else(|| PuzzleError::unsolvable("no hike reaches the bottom row"));
+}
+
+pub struct Day23Puzzle {}
+impl super::solve::Puzzle for Day23Puzzle {
+    fn solve(&self, document: &str) -> Result<Answer, PuzzleError> {
+        return Ok(get_longest_hike(document, true)?.into());
+    }
+
+    fn solve2(&self, document: &str) -> Result<Answer, PuzzleError> {
+        return Ok(get_longest_hike(document, false)?.into());
+    }
+}
+crate::register_puzzle!(
+    day: 23,
+    title: "A Long Walk",
+    tags: ["grid", "graph", "longest-path"],
+    puzzle: Day23Puzzle {},
+);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "#.#####################
+#.......#########...###
+#######.#########.#.###
+###.....#.>.>.###.#.###
+###v#####.#v#.###.#.###
+###.>...#.#.#.....#...#
+###v###.#.#.#########.#
+###...#.#.#.......#...#
+#####.#.#.#######.#.###
+#.....#.#.#.......#...#
+#.#####.#.#.#########v#
+#.#...#...#...###...>.#
+#.#.#v#######v###.###v#
+#...#.>.#...>.>.#.###.#
+#####v#.#.###v#.#.###.#
+#.....#...#...#.#.#...#
+#.#########.###.#.#.###
+#...###...#...#...#.###
+###.###.#.###v#####v###
+#...#...#.#.>.>.#.>.###
+#.###.###.#.###.#.#v###
+#.....###...###...#...#
+#####################.#";
+
+    #[test]
+    fn test_junctions() {
+        let map = TrailMap::parse(EXAMPLE).unwrap();
+        assert_eq!((map.start, map.end), ((0, 1), (22, 21)));
+        assert_eq!(map.junctions().len(), 9);
+
+        // A start with slopes beside it forks, but is still listed once, first.
+        let map = TrailMap::parse("#>.<#\n##.##\n##.##").unwrap();
+        assert_eq!(map.junctions(), vec![(0, 2), (2, 2)]);
+        assert_eq!(
+            TrailMap::parse("#.#\n###").err().unwrap().to_string(),
+            "unsolvable input: no path in the bottom row"
+        );
+    }
+
+    #[test]
+    fn test_trail_graph() {
+        let map = TrailMap::parse(EXAMPLE).unwrap();
+        let graph = TrailGraph::new(&map, true).unwrap();
+        assert_eq!(graph.edges[0].len(), 1);
+        assert_eq!(graph.edges[1].len(), 0);
+        let graph = TrailGraph::new(&map, false).unwrap();
+        assert_eq!(graph.edges[1].len(), 1);
+        let edges: usize = graph.edges.iter().map(Vec::len).sum();
+        assert_eq!(edges, 2 * 12);
+    }
+
+    #[test]
+    fn test_get_longest_hike() {
+        assert_eq!(get_longest_hike(EXAMPLE, true).unwrap(), 94);
+        assert_eq!(get_longest_hike(EXAMPLE, false).unwrap(), 154);
+    }
+}
@@ -0,0 +1,536 @@
+use std::cmp::Ordering;
+
+use crate::solve::{parse_token, Answer, PuzzleError};
+
+/// The smallest and largest X and Y of the test area in part 1 of the real puzzle.
+pub const TEST_AREA: (i64, i64) = (200000000000000, 400000000000000);
+
+/// The test area of the example in the puzzle text.
+pub const EXAMPLE_AREA: (i64, i64) = (7, 27);
+
+fn gcd(a: i128, b: i128) -> i128 {
+    let (mut a, mut b) = (a.abs(), b.abs());
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    return a;
+}
+
+// Checked arithmetic that also treats `i128::MIN` as an overflow, so every value it produces
+// can be negated.
+fn add(a: i128, b: i128) -> Option<i128> {
+    return a.checked_add(b).filter(|&value| value != i128::MIN);
+}
+
+fn mul(a: i128, b: i128) -> Option<i128> {
+    return a.checked_mul(b).filter(|&value| value != i128::MIN);
+}
+
+fn too_large() -> PuzzleError {
+    return PuzzleError::unsupported("the numbers are too large to calculate with exactly");
+}
+
+/// An exact rational number, always in lowest terms with a positive denominator. Arithmetic is
+/// checked and returns `None` if a result does not fit.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct Fraction {
+    num: i128,
+    den: i128,
+}
+impl Fraction {
+    pub fn new(num: i128, den: i128) -> Fraction {
+        assert!(den != 0, "fraction with a zero denominator");
+        let divisor = gcd(num, den) * den.signum();
+        return Fraction {
+            num: num / divisor,
+            den: den / divisor,
+        };
+    }
+
+    pub fn numerator(&self) -> i128 {
+        return self.num;
+    }
+
+    pub fn denominator(&self) -> i128 {
+        return self.den;
+    }
+
+    /// The value as an integer, if it is one.
+    pub fn to_integer(&self) -> Option<i128> {
+        return match self.den {
+            1 => Some(self.num),
+            _ => None,
+        };
+    }
+
+    pub fn checked_add(self, other: Fraction) -> Option<Fraction> {
+        // Working with the least common denominator keeps the products small.
+        let divisor = gcd(self.den, other.den);
+        return Some(Fraction::new(
+            add(
+                mul(self.num, other.den / divisor)?,
+                mul(other.num, self.den / divisor)?,
+            )?,
+            mul(self.den / divisor, other.den)?,
+        ));
+    }
+
+    pub fn checked_sub(self, other: Fraction) -> Option<Fraction> {
+        return self.checked_add(Fraction {
+            num: -other.num,
+            den: other.den,
+        });
+    }
+
+    pub fn checked_mul(self, other: Fraction) -> Option<Fraction> {
+        // Cancelling across first keeps the products small.
+        let a = gcd(self.num, other.den).max(1);
+        let b = gcd(other.num, self.den).max(1);
+        return Some(Fraction::new(
+            mul(self.num / a, other.num / b)?,
+            mul(self.den / b, other.den / a)?,
+        ));
+    }
+
+    /// `None` on overflow or division by zero.
+    pub fn checked_div(self, other: Fraction) -> Option<Fraction> {
+        if other.num == 0 {
+            return None;
+        }
+        return self.checked_mul(Fraction::new(other.den, other.num));
+    }
+}
+impl From<i128> for Fraction {
+    fn from(value: i128) -> Fraction {
+        return Fraction { num: value, den: 1 };
+    }
+}
+impl From<i64> for Fraction {
+    fn from(value: i64) -> Fraction {
+        return Fraction::from(value as i128);
+    }
+}
+impl Ord for Fraction {
+    /// Compares the whole parts, then the reciprocals of what is left, like continued fractions,
+    /// so nothing can overflow.
+    fn cmp(&self, other: &Self) -> Ordering {
+        let (mut a, mut b) = (*self, *other);
+        let mut flipped = false;
+        loop {
+            let (whole_a, rest_a) = (a.num.div_euclid(a.den), a.num.rem_euclid(a.den));
+            let (whole_b, rest_b) = (b.num.div_euclid(b.den), b.num.rem_euclid(b.den));
+            let result = match (whole_a.cmp(&whole_b), rest_a, rest_b) {
+                (Ordering::Equal, 0, 0) => Ordering::Equal,
+                (Ordering::Equal, 0, _) => Ordering::Less,
+                (Ordering::Equal, _, 0) => Ordering::Greater,
+                (Ordering::Equal, _, _) => {
+                    // rest_a / a.den < rest_b / b.den exactly when a.den / rest_a > b.den / rest_b.
+                    (a, b) = (
+                        Fraction {
+                            num: a.den,
+                            den: rest_a,
+                        },
+                        Fraction {
+                            num: b.den,
+                            den: rest_b,
+                        },
+                    );
+                    flipped = !flipped;
+                    continue;
+                }
+                (result, _, _) => result,
+            };
+            return match flipped {
+                true => result.reverse(),
+                false => result,
+            };
+        }
+    }
+}
+impl PartialOrd for Fraction {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        return Some(self.cmp(other));
+    }
+}
+
+type Vector = [i128; 3];
+
+fn sub(a: Vector, b: Vector) -> Option<Vector> {
+    return Some([add(a[0], -b[0])?, add(a[1], -b[1])?, add(a[2], -b[2])?]);
+}
+
+fn dot(a: Vector, b: Vector) -> Option<i128> {
+    return add(add(mul(a[0], b[0])?, mul(a[1], b[1])?)?, mul(a[2], b[2])?);
+}
+
+fn cross(a: Vector, b: Vector) -> Option<Vector> {
+    return Some([
+        add(mul(a[1], b[2])?, -mul(a[2], b[1])?)?,
+        add(mul(a[2], b[0])?, -mul(a[0], b[2])?)?,
+        add(mul(a[0], b[1])?, -mul(a[1], b[0])?)?,
+    ]);
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct Hailstone {
+    pub position: Vector,
+    pub velocity: Vector,
+}
+
+fn parse_vector(document: &str, text: &str) -> Result<Vector, PuzzleError> {
+    let coords = text
+        .split(',')
+        .map(|coord| parse_token::<i64>(document, coord.trim()))
+        .collect::<Result<Vec<i64>, PuzzleError>>()?;
+    let [x, y, z] = coords[..] else {
+        return Err(PuzzleError::parse(
+            document,
+            text.trim(),
+            format!("expected 3 coordinates, found {}", coords.len()),
+        ));
+    };
+    return Ok([x as i128, y as i128, z as i128]);
+}
+
+/// Parses one hailstone per line, as its position and velocity: `px, py, pz @ vx, vy, vz`.
+pub fn get_hailstones(document: &str) -> Result<Vec<Hailstone>, PuzzleError> {
+    return document
+        .lines()
+        .map(|line| {
+            let Some((position, velocity)) = line.split_once('@') else {
+                return Err(PuzzleError::missing(document, line, "missing `@`"));
+            };
+            return Ok(Hailstone {
+                position: parse_vector(document, position)?,
+                velocity: parse_vector(document, velocity)?,
+            });
+        })
+        .collect();
+}
+
+/// Where the future paths of `a` and `b` cross when only X and Y are considered, or `None` if
+/// they are parallel or cross in the past of either hailstone.
+pub fn get_crossing(
+    a: &Hailstone,
+    b: &Hailstone,
+) -> Result<Option<(Fraction, Fraction)>, PuzzleError> {
+    let crossing = || -> Option<Option<(Fraction, Fraction)>> {
+        let [vax, vay, _] = a.velocity;
+        let [vbx, vby, _] = b.velocity;
+        let det = add(mul(vbx, vay)?, -mul(vax, vby)?)?;
+        if det == 0 {
+            return Some(None);
+        }
+        let [dx, dy, _] = sub(b.position, a.position)?;
+        let t = Fraction::new(add(mul(vbx, dy)?, -mul(vby, dx)?)?, det);
+        let s = Fraction::new(add(mul(vax, dy)?, -mul(vay, dx)?)?, det);
+        if t < Fraction::from(0i128) || s < Fraction::from(0i128) {
+            return Some(None);
+        }
+        let along = |start: i128, speed: i128| {
+            return Fraction::from(start).checked_add(t.checked_mul(Fraction::from(speed))?);
+        };
+        return Some(Some((
+            along(a.position[0], vax)?,
+            along(a.position[1], vay)?,
+        )));
+    };
+    return crossing().ok_or_else(too_large);
+}
+
+/// The number of pairs of hailstones whose future paths cross within the test area, counting
+/// X and Y from `area.0` to `area.1` inclusive.
+pub fn count_crossings(hailstones: &[Hailstone], area: (i64, i64)) -> Result<usize, PuzzleError> {
+    let (min, max) = (Fraction::from(area.0), Fraction::from(area.1));
+    let inside = |value: Fraction| min <= value && value <= max;
+    let mut count = 0;
+    for (i, a) in hailstones.iter().enumerate() {
+        for b in hailstones[i + 1..].iter() {
+            if let Some((x, y)) = get_crossing(a, b)? {
+                if inside(x) && inside(y) {
+                    count += 1;
+                }
+            }
+        }
+    }
+    return Ok(count);
+}
+
+/// The moment `stone` hits the plane through the origin with normal `normal`, and where, or
+/// `Some(None)` if it moves parallel to the plane. `None` if the numbers get too large.
+fn hit_plane(stone: &Hailstone, normal: Vector) -> Option<Option<(Fraction, [Fraction; 3])>> {
+    let speed = dot(stone.velocity, normal)?;
+    if speed == 0 {
+        return Some(None);
+    }
+    let time = Fraction::new(-dot(stone.position, normal)?, speed);
+    let mut point = [Fraction::from(0i128); 3];
+    for (axis, coord) in point.iter_mut().enumerate() {
+        *coord = Fraction::from(stone.position[axis])
+            .checked_add(time.checked_mul(Fraction::from(stone.velocity[axis]))?)?;
+    }
+    return Some(Some((time, point)));
+}
+
+/// The path through `p1` at time `t1` and `p2` at time `t2`, as a position at time 0 and a
+/// velocity.
+fn path_through(
+    (t1, p1): (Fraction, [Fraction; 3]),
+    (t2, p2): (Fraction, [Fraction; 3]),
+) -> Option<([Fraction; 3], [Fraction; 3])> {
+    let mut position = [Fraction::from(0i128); 3];
+    let mut velocity = [Fraction::from(0i128); 3];
+    let elapsed = t2.checked_sub(t1)?;
+    for axis in 0..3 {
+        velocity[axis] = p2[axis].checked_sub(p1[axis])?.checked_div(elapsed)?;
+        position[axis] = p1[axis].checked_sub(t1.checked_mul(velocity[axis])?)?;
+    }
+    return Some((position, velocity));
+}
+
+/// The rock that hits every hailstone when thrown from a whole-number position with a
+/// whole-number velocity.
+///
+/// Seen from the first hailstone, the rock passes through the origin, so it moves in the plane
+/// through the origin and the path of any second hailstone. Where two more hailstones cross
+/// that plane, and when, fixes the rock's path. The answer is then checked against all
+/// hailstones.
+pub fn find_rock(hailstones: &[Hailstone]) -> Result<Hailstone, PuzzleError> {
+    let Some(first) = hailstones.first() else {
+        return Err(PuzzleError::unsolvable("no hailstones"));
+    };
+    let relative: Vec<Hailstone> = hailstones[1..]
+        .iter()
+        .map(|stone| {
+            return Some(Hailstone {
+                position: sub(stone.position, first.position)?,
+                velocity: sub(stone.velocity, first.velocity)?,
+            });
+        })
+        .collect::<Option<Vec<Hailstone>>>()
+        .ok_or_else(too_large)?;
+
+    let mut rock = None;
+    'search: for (i, second) in relative.iter().enumerate() {
+        let normal = cross(second.position, second.velocity).ok_or_else(too_large)?;
+        if normal == [0, 0, 0] {
+            continue;
+        }
+        let hits: Vec<(Fraction, [Fraction; 3])> = relative
+            .iter()
+            .enumerate()
+            .filter(|&(j, _)| j != i)
+            .map(|(_, stone)| hit_plane(stone, normal))
+            .collect::<Option<Vec<_>>>()
+            .ok_or_else(too_large)?
+            .into_iter()
+            .flatten()
+            .collect();
+        for (k, &first_hit) in hits.iter().enumerate() {
+            for &second_hit in hits[k + 1..].iter() {
+                if first_hit.0 == second_hit.0 {
+                    continue;
+                }
+                rock = Some(path_through(first_hit, second_hit).ok_or_else(too_large)?);
+                break 'search;
+            }
+        }
+    }
+
+    let Some((position, velocity)) = rock else {
+        return Err(PuzzleError::unsolvable(
+            "the hailstones do not fix a single path for the rock",
+        ));
+    };
+    let whole = |values: [Fraction; 3]| -> Option<Vector> {
+        return Some([
+            values[0].to_integer()?,
+            values[1].to_integer()?,
+            values[2].to_integer()?,
+        ]);
+    };
+    let (Some(position), Some(velocity)) = (whole(position), whole(velocity)) else {
+        return Err(PuzzleError::unsolvable(
+            "the rock would need a fractional position or velocity",
+        ));
+    };
+    let negated = |vector: Vector| vector.map(|value| -value);
+    let rock = Hailstone {
+        position: sub(position, negated(first.position)).ok_or_else(too_large)?,
+        velocity: sub(velocity, negated(first.velocity)).ok_or_else(too_large)?,
+    };
+    // The rock hits a hailstone if their paths meet at the same moment, which is exactly when
+    // the hailstone's offset from the rock is parallel to their relative velocity.
+    for stone in hailstones {
+        let parallel = || -> Option<bool> {
+            let offset = sub(stone.position, rock.position)?;
+            let closing = sub(rock.velocity, stone.velocity)?;
+            return Some(cross(offset, closing)? == [0, 0, 0]);
+        };
+        if !parallel().ok_or_else(too_large)? {
+            return Err(PuzzleError::unsolvable(
+                "no single rock hits every hailstone",
+            ));
+        }
+    }
+    return Ok(rock);
+}
+
+pub struct Day24Puzzle {
+    /// The smallest and largest X and Y of the test area in part 1.
+    pub test_area: (i64, i64),
+}
+impl super::solve::Puzzle for Day24Puzzle {
+    fn solve(&self, document: &str) -> Result<Answer, PuzzleError> {
+        let hailstones = get_hailstones(document)?;
+        return Ok(count_crossings(&hailstones, self.test_area)?.into());
+    }
+
+    fn solve2(&self, document: &str) -> Result<Answer, PuzzleError> {
+        let rock = find_rock(&get_hailstones(document)?)?;
+        let sum = rock
+            .position
+            .iter()
+            .try_fold(0, |sum, &coord| add(sum, coord))
+            .ok_or_else(too_large)?;
+        return Ok(sum.into());
+    }
+}
+crate::register_puzzle!(
+    day: 24,
+    title: "Never Tell Me The Odds",
+    tags: ["parsing", "geometry", "linear-algebra"],
+    puzzle: Day24Puzzle {
+        test_area: TEST_AREA,
+    },
+    example: Day24Puzzle {
+        test_area: EXAMPLE_AREA,
+    },
+);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solve::Puzzle;
+
+    const EXAMPLE: &str = "19, 13, 30 @ -2,  1, -2
+18, 19, 22 @ -1, -1, -2
+20, 25, 34 @ -2, -2, -4
+12, 31, 28 @ -1, -2, -1
+20, 19, 15 @  1, -5, -3";
+
+    #[test]
+    fn test_fraction() {
+        assert_eq!(Fraction::new(4, -6), Fraction::new(-2, 3));
+        assert_eq!(
+            Fraction::new(1, 6).checked_add(Fraction::new(1, 3)),
+            Some(Fraction::new(1, 2))
+        );
+        assert_eq!(
+            Fraction::new(1, 6).checked_sub(Fraction::new(1, 3)),
+            Some(Fraction::new(-1, 6))
+        );
+        assert_eq!(
+            Fraction::new(3, 4).checked_mul(Fraction::new(2, 9)),
+            Some(Fraction::new(1, 6))
+        );
+        assert_eq!(
+            Fraction::new(1, 2).checked_div(Fraction::new(-1, 4)),
+            Some(Fraction::from(-2i128))
+        );
+        assert_eq!(Fraction::new(1, 2).checked_div(Fraction::from(0i128)), None);
+        let huge = Fraction::from(i128::MAX / 2);
+        assert_eq!(huge.checked_mul(Fraction::from(3i128)), None);
+        assert_eq!(
+            huge.checked_add(huge).and_then(|sum| sum.checked_add(huge)),
+            None
+        );
+
+        assert!(Fraction::new(-1, 3) < Fraction::new(-1, 4));
+        assert!(Fraction::new(2, 7) < Fraction::new(3, 10));
+        assert!(Fraction::new(5, 3) > Fraction::new(3, 2));
+        assert_eq!(
+            Fraction::new(4, 6).cmp(&Fraction::new(2, 3)),
+            Ordering::Equal
+        );
+        assert!(
+            Fraction::new(i128::MAX - 1, i128::MAX) > Fraction::new(i128::MAX - 2, i128::MAX - 1)
+        );
+        assert_eq!(Fraction::new(6, 3).to_integer(), Some(2));
+        assert_eq!(Fraction::new(7, 3).to_integer(), None);
+    }
+
+    #[test]
+    fn test_get_hailstones() {
+        let hailstones = get_hailstones(EXAMPLE).unwrap();
+        assert_eq!(hailstones.len(), 5);
+        assert_eq!(
+            hailstones[4],
+            Hailstone {
+                position: [20, 19, 15],
+                velocity: [1, -5, -3],
+            }
+        );
+        assert_eq!(
+            get_hailstones("19, 13, 30 -2, 1, -2")
+                .unwrap_err()
+                .to_string(),
+            "parse error at line 1, column 21: missing `@`"
+        );
+        assert_eq!(
+            get_hailstones("19, 13 @ -2, 1, -2")
+                .unwrap_err()
+                .to_string(),
+            "parse error at line 1, column 1: expected 3 coordinates, found 2"
+        );
+    }
+
+    #[test]
+    fn test_get_crossing() {
+        let hailstones = get_hailstones(EXAMPLE).unwrap();
+        assert_eq!(
+            get_crossing(&hailstones[0], &hailstones[1]),
+            Ok(Some((Fraction::new(43, 3), Fraction::new(46, 3))))
+        );
+        assert_eq!(get_crossing(&hailstones[1], &hailstones[2]), Ok(None));
+        assert_eq!(get_crossing(&hailstones[0], &hailstones[4]), Ok(None));
+        assert_eq!(count_crossings(&hailstones, (7, 27)), Ok(2));
+    }
+
+    #[test]
+    fn test_find_rock() {
+        let rock = find_rock(&get_hailstones(EXAMPLE).unwrap()).unwrap();
+        assert_eq!(rock.position, [24, 13, 10]);
+        assert_eq!(rock.velocity, [-3, 1, 2]);
+        assert!(
+            find_rock(&get_hailstones("0, 0, 0 @ 1, 0, 0\n5, 5, 5 @ 0, 1, 0").unwrap()).is_err()
+        );
+    }
+
+    #[test]
+    fn test_solve() {
+        let puzzle = Day24Puzzle {
+            test_area: EXAMPLE_AREA,
+        };
+        assert_eq!(puzzle.solve(EXAMPLE).unwrap(), Answer::from(2));
+        assert_eq!(puzzle.solve2(EXAMPLE).unwrap(), Answer::from(47));
+
+        let document = "99999999999999999999999999999, 13, 30 @ -2000000000, 1, -2\n\
+                        18, 99999999999999999999999, 22 @ -1, -1, -2\n\
+                        20, 25, 34 @ -2, -2, -4";
+        assert!(matches!(
+            puzzle.solve(document),
+            Err(PuzzleError::Parse { .. })
+        ));
+        let document = "9000000000000000000, 13, 30 @ -2000000000, 1, -2\n\
+                        18, 9000000000000000000, 22 @ -1, -1, -2\n\
+                        20, 25, 34 @ -2, -2, -4\n\
+                        -9000000000000000000, 7, 1 @ 3, 9000000000000000000, 5";
+        for result in [puzzle.solve(document), puzzle.solve2(document)] {
+            assert_eq!(
+                result.unwrap_err().to_string(),
+                "unsupported input: the numbers are too large to calculate with exactly"
+            );
+        }
+    }
+}
@@ -0,0 +1,233 @@
+use std::collections::{HashMap, VecDeque};
+
+use crate::solve::{Answer, PuzzleError};
+
+/// The components and the wires connecting them. Every wire connects two components and can
+/// carry one unit of flow in either direction.
+pub struct Diagram {
+    pub names: Vec<String>,
+    pub wires: Vec<(usize, usize)>,
+    /// `adjacency[i]`: the neighbors of component `i`, each with the index of the wire to it.
+    pub adjacency: Vec<Vec<(usize, usize)>>,
+}
+impl Diagram {
+    /// Parses one line per component, listing the components it is wired to: `jqt: rhn xhk`.
+    pub fn parse(document: &str) -> Result<Diagram, PuzzleError> {
+        let mut diagram = Diagram {
+            names: Vec::new(),
+            wires: Vec::new(),
+            adjacency: Vec::new(),
+        };
+        let mut index: HashMap<&str, usize> = HashMap::new();
+        for line in document.lines() {
+            let Some((name, others)) = line.split_once(':') else {
+                return Err(PuzzleError::missing(document, line, "missing `:`"));
+            };
+            if name.trim().is_empty() {
+                return Err(PuzzleError::parse(document, line, "missing component name"));
+            }
+            let a = diagram.component(&mut index, name.trim());
+            for other in others.split_whitespace() {
+                let b = diagram.component(&mut index, other);
+                let wire = diagram.wires.len();
+                diagram.wires.push((a, b));
+                diagram.adjacency[a].push((b, wire));
+                diagram.adjacency[b].push((a, wire));
+            }
+        }
+        return Ok(diagram);
+    }
+
+    /// The index of the component called `name`, adding it if it is new.
+    fn component<'a>(&mut self, index: &mut HashMap<&'a str, usize>, name: &'a str) -> usize {
+        return *index.entry(name).or_insert_with(|| {
+            self.names.push(name.to_string());
+            self.adjacency.push(Vec::new());
+            return self.names.len() - 1;
+        });
+    }
+}
+
+/// A smallest set of wires whose removal splits the diagram in two.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Cut {
+    /// The wires to cut, as the names of the components they connect, sorted.
+    pub wires: Vec<(String, String)>,
+    /// The number of components in each of the two groups.
+    pub sizes: (usize, usize),
+}
+
+/// Pushes flow from `source` to `sink` along shortest augmenting paths until `limit` units flow
+/// or no path is left. Returns how much flows and which components the source can still reach.
+fn max_flow(diagram: &Diagram, source: usize, sink: usize, limit: usize) -> (usize, Vec<bool>) {
+    // `flow[w]` is the flow along wire `w` from its first component to its second.
+    let mut flow = vec![0i32; diagram.wires.len()];
+    let mut total = 0;
+    loop {
+        let mut came_from: Vec<Option<(usize, usize)>> = vec![None; diagram.names.len()];
+        let mut reached = vec![false; diagram.names.len()];
+        reached[source] = true;
+        let mut queue = VecDeque::from([source]);
+        while let Some(current) = queue.pop_front() {
+            for &(next, wire) in diagram.adjacency[current].iter() {
+                let forward = diagram.wires[wire].0 == current;
+                let spare = match forward {
+                    true => 1 - flow[wire],
+                    false => 1 + flow[wire],
+                };
+                if spare > 0 && !reached[next] {
+                    reached[next] = true;
+                    came_from[next] = Some((current, wire));
+                    queue.push_back(next);
+                }
+            }
+        }
+        if !reached[sink] || total == limit {
+            return (total, reached);
+        }
+        let mut current = sink;
+        while let Some((previous, wire)) = came_from[current] {
+            flow[wire] += match diagram.wires[wire].0 == previous {
+                true => 1,
+                false => -1,
+            };
+            current = previous;
+        }
+        total += 1;
+    }
+}
+
+/// Finds a minimum cut by computing the maximum flow from the first component to every other
+/// one: the smallest of these flows is the size of the minimum cut, and the wires leading out
+/// of what the first component can still reach form the cut itself.
+pub fn find_minimum_cut(diagram: &Diagram) -> Result<Cut, PuzzleError> {
+    if diagram.names.len() < 2 {
+        return Err(PuzzleError::unsolvable("fewer than 2 components to split"));
+    }
+    let mut best: Option<(usize, Vec<bool>)> = None;
+    for sink in 1..diagram.names.len() {
+        // Flows as large as the best cut so far cannot lead to a smaller one.
+        let limit = best.as_ref().map_or(diagram.wires.len(), |(size, _)| *size);
+        let (size, reached) = max_flow(diagram, 0, sink, limit);
+        if size < limit || best.is_none() {
+            best = Some((size, reached));
+        }
+    }
+    let (_, reached) = best.unwrap();
+
+    let mut wires: Vec<(String, String)> = diagram
+        .wires
+        .iter()
+        .filter(|&&(a, b)| reached[a] != reached[b])
+        .map(|&(a, b)| {
+            let (a, b) = (&diagram.names[a], &diagram.names[b]);
+            return (a.min(b).clone(), a.max(b).clone());
+        })
+        .collect();
+    wires.sort();
+    let inside = reached.iter().filter(|&&reached| reached).count();
+    return Ok(Cut {
+        wires,
+        sizes: (inside, diagram.names.len() - inside),
+    });
+}
+
+/// Finds the minimum cut and checks it is the three wires the puzzle asks for.
+fn get_three_wire_cut(document: &str) -> Result<Cut, PuzzleError> {
+    let cut = find_minimum_cut(&Diagram::parse(document)?)?;
+    if cut.wires.len() != 3 {
+        return Err(PuzzleError::unsolvable(format!(
+            "the smallest cut has {} wires instead of 3",
+            cut.wires.len()
+        )));
+    }
+    return Ok(cut);
+}
+
+pub struct Day25Puzzle {}
+impl super::solve::Puzzle for Day25Puzzle {
+    fn solve(&self, document: &str) -> Result<Answer, PuzzleError> {
+        let cut = get_three_wire_cut(document)?;
+        return Ok((cut.sizes.0 * cut.sizes.1).into());
+    }
+
+    /// Day 25 has no second part. The wires to cut are available from [`find_minimum_cut`].
+    fn solve2(&self, _document: &str) -> Result<Answer, PuzzleError> {
+        return Err(PuzzleError::unsupported("day 25 has no second part"));
+    }
+
+    fn has_part2(&self) -> bool {
+        return false;
+    }
+}
+crate::register_puzzle!(
+    day: 25,
+    title: "Snowverload",
+    tags: ["parsing", "graph", "min-cut"],
+    puzzle: Day25Puzzle {},
+);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solve::Puzzle;
+
+    const EXAMPLE: &str = "jqt: rhn xhk nvd
+rsh: frs pzl lsr
+xhk: hfx
+cmg: qnr nvd lhk bvb
+rhn: xhk bvb hfx
+bvb: xhk hfx
+pzl: lsr hfx nvd
+qnr: nvd
+ntq: jqt hfx bvb xhk
+nvd: lhk
+lsr: lhk
+rzs: qnr cmg lsr rsh
+frs: qnr lhk lsr";
+
+    #[test]
+    fn test_parse() {
+        let diagram = Diagram::parse(EXAMPLE).unwrap();
+        assert_eq!(diagram.names.len(), 15);
+        assert_eq!(diagram.wires.len(), 33);
+        assert_eq!(diagram.adjacency[0].len(), 4);
+        assert_eq!(
+            Diagram::parse("jqt: rhn\nrsh frs")
+                .err()
+                .unwrap()
+                .to_string(),
+            "parse error at line 2, column 8: missing `:`"
+        );
+    }
+
+    #[test]
+    fn test_find_minimum_cut() {
+        let cut = find_minimum_cut(&Diagram::parse(EXAMPLE).unwrap()).unwrap();
+        assert_eq!(
+            cut.wires,
+            vec![
+                ("bvb".to_string(), "cmg".to_string()),
+                ("hfx".to_string(), "pzl".to_string()),
+                ("jqt".to_string(), "nvd".to_string()),
+            ]
+        );
+        assert_eq!(cut.sizes.0 * cut.sizes.1, 54);
+        assert_eq!(cut.sizes.0 + cut.sizes.1, 15);
+
+        let cut = find_minimum_cut(&Diagram::parse("a: b\nc: d").unwrap()).unwrap();
+        assert_eq!(cut.wires, vec![]);
+        assert_eq!(cut.sizes, (2, 2));
+    }
+
+    #[test]
+    fn test_solve() {
+        let puzzle = Day25Puzzle {};
+        assert_eq!(puzzle.solve(EXAMPLE).unwrap(), Answer::from(54));
+        assert_eq!(
+            puzzle.solve2(EXAMPLE).unwrap_err().to_string(),
+            "unsupported input: day 25 has no second part"
+        );
+        assert!(!puzzle.has_part2());
+    }
+}
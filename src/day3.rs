@@ -1,6 +1,8 @@
 use std::collections::{HashMap, HashSet};
 
-fn get_symbol_positions(schematic: &str) -> Vec<Vec<bool>> {
+use crate::solve::{grid_size, parse_token, Answer, PuzzleError};
+
+pub fn get_symbol_positions(schematic: &str) -> Vec<Vec<bool>> {
     let h: usize = schematic.lines().count();
     let l: usize = schematic.lines().nth(0).unwrap().chars().count();
 
@@ -22,32 +24,43 @@ fn get_symbol_positions(schematic: &str) -> Vec<Vec<bool>> {
     return positions;
 }
 
-fn sum_line_numbers(line: &str, positions: &Vec<bool>) -> i32 {
+fn too_large() -> PuzzleError {
+    return PuzzleError::unsupported("the total is too large");
+}
+
+/// Sums the numbers in `line`, a line of `schematic`, that touch a symbol.
+pub fn sum_line_numbers(
+    schematic: &str,
+    line: &str,
+    positions: &Vec<bool>,
+) -> Result<i32, PuzzleError> {
     let mut sum: i32 = 0;
-    let mut buffer: String = String::new();
+    let mut start: Option<usize> = None;
     let mut active_valid: bool = false;
 
-    for (i, c) in line.chars().enumerate() {
+    for (i, (offset, c)) in line.char_indices().enumerate() {
         if c.is_digit(10) {
-            buffer.push(c);
+            start.get_or_insert(offset);
             active_valid |= positions[i];
         } else {
-            if active_valid {
-                sum += buffer.parse::<i32>().unwrap();
+            if let (Some(start), true) = (start, active_valid) {
+                let num = parse_token::<i32>(schematic, &line[start..offset])?;
+                sum = sum.checked_add(num).ok_or_else(too_large)?;
             }
-            buffer = String::new();
+            start = None;
             active_valid = false;
         }
     }
 
-    if active_valid {
-        sum += buffer.parse::<i32>().unwrap();
+    if let (Some(start), true) = (start, active_valid) {
+        let num = parse_token::<i32>(schematic, &line[start..])?;
+        sum = sum.checked_add(num).ok_or_else(too_large)?;
     }
 
-    return sum;
+    return Ok(sum);
 }
 
-fn get_potential_gears(schematic: &str) -> Vec<Vec<Vec<(usize, usize)>>> {
+pub fn get_potential_gears(schematic: &str) -> Vec<Vec<Vec<(usize, usize)>>> {
     let h: usize = schematic.lines().count();
     let l: usize = schematic.lines().nth(0).unwrap().chars().count();
 
@@ -69,71 +82,71 @@ fn get_potential_gears(schematic: &str) -> Vec<Vec<Vec<(usize, usize)>>> {
     return positions;
 }
 
-fn get_gear_values(schematic: &str) -> HashMap<(usize, usize), Vec<i32>> {
+pub fn get_gear_values(schematic: &str) -> Result<HashMap<(usize, usize), Vec<i32>>, PuzzleError> {
     let positions = get_potential_gears(schematic);
 
     let mut gear_values: HashMap<(usize, usize), Vec<i32>> = HashMap::new();
-    let mut buffer: String = String::new();
     let mut active_gears: HashSet<(usize, usize)> = HashSet::new();
 
-    fn end_num(
-        buffer: &mut String,
-        active_gears: &mut HashSet<(usize, usize)>,
-        gear_values: &mut HashMap<(usize, usize), Vec<i32>>,
-    ) -> () {
-        if buffer.len() == 0 {
-            return;
-        }
-        let num = buffer.parse::<i32>().unwrap();
-        for gear in active_gears.iter() {
-            gear_values.entry(*gear).or_insert(Vec::new()).push(num);
-        }
-        buffer.clear();
-        active_gears.clear();
-    }
-
     for (i, line) in schematic.lines().enumerate() {
-        for (j, c) in line.chars().enumerate() {
+        let mut start: Option<usize> = None;
+        let chars = line.char_indices().chain([(line.len(), '.')]);
+        for (j, (offset, c)) in chars.enumerate() {
             if c.is_digit(10) {
-                buffer.push(c);
+                start.get_or_insert(offset);
                 active_gears.extend(positions[i][j].clone());
-            } else {
-                end_num(&mut buffer, &mut active_gears, &mut gear_values);
+                continue;
+            }
+            let Some(number_start) = start.take() else {
+                continue;
+            };
+            let num = parse_token::<i32>(schematic, &line[number_start..offset])?;
+            for gear in active_gears.drain() {
+                gear_values.entry(gear).or_insert(Vec::new()).push(num);
             }
         }
-        end_num(&mut buffer, &mut active_gears, &mut gear_values);
     }
-    end_num(&mut buffer, &mut active_gears, &mut gear_values);
-    return gear_values;
+    return Ok(gear_values);
 }
 
-fn sum_gear_ratios(gear_values: &HashMap<(usize, usize), Vec<i32>>) -> i32 {
+pub fn sum_gear_ratios(
+    gear_values: &HashMap<(usize, usize), Vec<i32>>,
+) -> Result<i32, PuzzleError> {
     let mut sum: i32 = 0;
     for (_, values) in gear_values.iter() {
         if values.len() != 2 {
             continue;
         }
-        sum += values[0] * values[1];
+        let ratio = values[0].checked_mul(values[1]).ok_or_else(too_large)?;
+        sum = sum.checked_add(ratio).ok_or_else(too_large)?;
     }
-    return sum;
+    return Ok(sum);
 }
 
 pub struct Day3Puzzle {}
-impl super::solve::Puzzle<String> for Day3Puzzle {
-    fn solve(&self, document: &str) -> String {
+impl super::solve::Puzzle for Day3Puzzle {
+    fn solve(&self, document: &str) -> Result<Answer, PuzzleError> {
+        grid_size(document)?;
         let positions = get_symbol_positions(document);
-        return document
-            .lines()
-            .zip(positions.iter())
-            .map(|(line, pos_row)| sum_line_numbers(line, pos_row))
-            .sum::<i32>()
-            .to_string();
+        let mut sum: i32 = 0;
+        for (line, pos_row) in document.lines().zip(positions.iter()) {
+            let line_sum = sum_line_numbers(document, line, pos_row)?;
+            sum = sum.checked_add(line_sum).ok_or_else(too_large)?;
+        }
+        return Ok(sum.into());
     }
 
-    fn solve2(&self, document: &str) -> String {
-        return sum_gear_ratios(&get_gear_values(document)).to_string();
+    fn solve2(&self, document: &str) -> Result<Answer, PuzzleError> {
+        grid_size(document)?;
+        return Ok(sum_gear_ratios(&get_gear_values(document)?)?.into());
     }
 }
+crate::register_puzzle!(
+    day: 3,
+    title: "Gear Ratios",
+    tags: ["grid", "parsing"],
+    puzzle: Day3Puzzle {},
+);
 
 #[cfg(test)]
 mod tests {
@@ -167,19 +180,28 @@ mod tests {
     #[test]
     fn test_sum_line_numbers() {
         assert_eq!(
-            sum_line_numbers("1.2.3", &vec![false, false, false, false, false]),
-            0
+            sum_line_numbers("1.2.3", "1.2.3", &vec![false, false, false, false, false]),
+            Ok(0)
+        );
+        assert_eq!(
+            sum_line_numbers("1.2.3", "1.2.3", &vec![false, true, false, true, false]),
+            Ok(0)
         );
         assert_eq!(
-            sum_line_numbers("1.2.3", &vec![false, true, false, true, false]),
-            0
+            sum_line_numbers("1.2.3", "1.2.3", &vec![true, true, true, true, false]),
+            Ok(3)
         );
+        assert_eq!(sum_line_numbers("12.13", "12.13", &vec![true; 5]), Ok(25));
+        assert_eq!(sum_line_numbers("12#13", "12#13", &vec![true; 5]), Ok(25));
+
+        let line = "12345678901234567890abc";
         assert_eq!(
-            sum_line_numbers("1.2.3", &vec![true, true, true, true, false]),
-            3
+            sum_line_numbers(line, line, &vec![true; 23])
+                .unwrap_err()
+                .to_string(),
+            "parse error at line 1, column 1: invalid value `12345678901234567890`: \
+             number too large to fit in target type"
         );
-        assert_eq!(sum_line_numbers("12.13", &vec![true; 5]), 25);
-        assert_eq!(sum_line_numbers("12#13", &vec![true; 5]), 25);
     }
 
     #[test]
@@ -197,9 +219,16 @@ mod tests {
     #[test]
     fn test_get_gear_values() {
         assert_eq!(
-            get_gear_values("1*2\n35."),
+            get_gear_values("1*2\n35.").unwrap(),
             HashMap::from_iter(vec![((0, 1), vec![1, 2, 35])])
         );
+        assert_eq!(
+            get_gear_values("1*2...................\n.*12345678901234567890")
+                .unwrap_err()
+                .to_string(),
+            "parse error at line 2, column 3: invalid value `12345678901234567890`: \
+             number too large to fit in target type"
+        );
     }
 
     #[test]
@@ -210,7 +239,7 @@ mod tests {
                 ((0, 2), vec![3, 4, 5]),
                 ((1, 1), vec![6]),
             ])),
-            2
+            Ok(2)
         );
     }
 }
@@ -1,54 +1,75 @@
 use std::collections::HashSet;
 
-fn get_numbers(text: &str) -> HashSet<i32> {
+use crate::solve::{parse_token, Answer, PuzzleError};
+
+pub fn get_numbers(text: &str) -> Result<HashSet<i32>, PuzzleError> {
     return text
         .split_whitespace()
-        .map(|x| x.parse::<i32>().unwrap())
+        .map(|x| parse_token::<i32>(text, x))
         .collect();
 }
 
-fn get_card_matches(card: &str) -> usize {
-    let trimmed_card: &str = card.split(':').nth(1).unwrap().trim();
-
-    let mut numbers = trimmed_card.split('|').map(|x| get_numbers(x.trim()));
+pub fn get_card_matches(card: &str) -> Result<usize, PuzzleError> {
+    let Some((_, numbers)) = card.split_once(':') else {
+        return Err(PuzzleError::missing(card, card, "expected `:`"));
+    };
+    let Some((have_numbers, winning_numbers)) = numbers.split_once('|') else {
+        return Err(PuzzleError::missing(card, card, "expected `|`"));
+    };
 
-    let have_numbers: HashSet<i32> = numbers.next().unwrap();
-    let winning_numbers: HashSet<i32> = numbers.next().unwrap();
+    let have_numbers: HashSet<i32> =
+        get_numbers(have_numbers).map_err(|err| err.within(card, have_numbers))?;
+    let winning_numbers: HashSet<i32> =
+        get_numbers(winning_numbers).map_err(|err| err.within(card, winning_numbers))?;
 
     let matches = have_numbers.intersection(&winning_numbers).count();
-    return matches;
+    return Ok(matches);
+}
+
+pub fn get_all_card_matches(document: &str) -> Result<Vec<usize>, PuzzleError> {
+    return document
+        .lines()
+        .map(|card| get_card_matches(card).map_err(|err| err.within(document, card)))
+        .collect();
 }
 
-fn get_total_cards(document: &str) -> i32 {
-    let mut counts: Vec<i32> = document.lines().map(|_| 1).collect();
+pub fn get_total_cards(document: &str) -> Result<i32, PuzzleError> {
+    let matches = get_all_card_matches(document)?;
+    let mut counts: Vec<i32> = matches.iter().map(|_| 1).collect();
 
-    for (i, card) in document.lines().enumerate() {
-        let wins = get_card_matches(card);
+    for (i, wins) in matches.iter().enumerate() {
+        if i + wins >= counts.len() {
+            return Err(PuzzleError::unsolvable(format!(
+                "card {} wins copies of cards past the end of the table",
+                i + 1
+            )));
+        }
         for j in 1..wins + 1 {
             counts[i + j] += counts[i];
         }
     }
 
-    return counts.iter().sum::<i32>() as i32;
+    return Ok(counts.iter().sum::<i32>() as i32);
 }
 
 pub struct Day4Puzzle {}
-impl super::solve::Puzzle<String> for Day4Puzzle {
-    fn solve(&self, document: &str) -> String {
-        return document
-            .lines()
-            .map(|line| match get_card_matches(line) {
+impl super::solve::Puzzle for Day4Puzzle {
+    fn solve(&self, document: &str) -> Result<Answer, PuzzleError> {
+        return Ok(get_all_card_matches(document)?
+            .iter()
+            .map(|matches| match matches {
                 0 => 0,
-                n @ _ => 2_i32.pow(n as u32 - 1),
+                n @ _ => 2_i32.pow(*n as u32 - 1),
             })
             .sum::<i32>()
-            .to_string();
+            .into());
     }
 
-    fn solve2(&self, document: &str) -> String {
-        return get_total_cards(document).to_string();
+    fn solve2(&self, document: &str) -> Result<Answer, PuzzleError> {
+        return Ok(get_total_cards(document)?.into());
     }
 }
+crate::register_puzzle!(day: 4, title: "Scratchcards", tags: ["parsing"], puzzle: Day4Puzzle {});
 
 #[cfg(test)]
 mod tests {
@@ -56,15 +77,21 @@ mod tests {
 
     #[test]
     fn test_get_numbers() {
-        assert_eq!(get_numbers("1 2 3"), HashSet::from([1, 2, 3]));
+        assert_eq!(get_numbers("1 2 3"), Ok(HashSet::from([1, 2, 3])));
         assert_eq!(
             get_numbers(" 1  2  3 40  5"),
-            HashSet::from([1, 2, 3, 40, 5])
+            Ok(HashSet::from([1, 2, 3, 40, 5]))
         );
     }
 
     #[test]
     fn test_get_score() {
-        assert_eq!(get_card_matches("Card 1: 1 2 3 | 2"), 1)
+        assert_eq!(get_card_matches("Card 1: 1 2 3 | 2"), Ok(1));
+        assert_eq!(
+            get_card_matches("Card 1: 1 2 3 | 2 x")
+                .unwrap_err()
+                .to_string(),
+            "parse error at line 1, column 19: invalid value `x`: invalid digit found in string"
+        );
     }
 }
@@ -1,25 +1,31 @@
 use itertools::Itertools;
 
-fn read_single_map(text: &str) -> (i64, i64, i64) {
-    let tuple: (i64, i64, i64) = text
+use crate::solve::{parse_token, Answer, PuzzleError};
+
+pub fn read_single_map(text: &str) -> Result<(i64, i64, i64), PuzzleError> {
+    let Some(tuple): Option<(i64, i64, i64)> = text
         .split_whitespace()
-        .map(|line| line.parse::<i64>().unwrap())
+        .map(|line| parse_token::<i64>(text, line))
+        .collect::<Result<Vec<i64>, PuzzleError>>()?
+        .into_iter()
         .collect_tuple()
-        .unwrap();
-    return (tuple.1, tuple.0, tuple.2);
+    else {
+        return Err(PuzzleError::parse(text, text, "expected three numbers"));
+    };
+    return Ok((tuple.1, tuple.0, tuple.2));
 }
 
-fn read_maps(text: &str) -> Vec<(i64, i64, i64)> {
+pub fn read_maps(text: &str) -> Result<Vec<(i64, i64, i64)>, PuzzleError> {
     let mut maps = text
         .lines()
         .skip(1)
-        .map(|line| read_single_map(line))
-        .collect::<Vec<(i64, i64, i64)>>();
+        .map(|line| read_single_map(line).map_err(|err| err.within(text, line)))
+        .collect::<Result<Vec<(i64, i64, i64)>, PuzzleError>>()?;
     maps.sort_by(|a, b| a.0.cmp(&b.0));
-    return maps;
+    return Ok(maps);
 }
 
-fn convert_through_maps(maps: &Vec<(i64, i64, i64)>, value: i64) -> i64 {
+pub fn convert_through_maps(maps: &Vec<(i64, i64, i64)>, value: i64) -> i64 {
     let idx = maps
         .binary_search(&(value, i64::MAX, i64::MAX))
         .unwrap_or_else(|x| x);
@@ -34,37 +40,39 @@ fn convert_through_maps(maps: &Vec<(i64, i64, i64)>, value: i64) -> i64 {
     return value;
 }
 
-fn split_almanac(almanac: &str) -> (Vec<i64>, std::str::Split<'_, &str>) {
+pub fn split_almanac(almanac: &str) -> Result<(Vec<i64>, std::str::Split<'_, &str>), PuzzleError> {
     let mut chunks = almanac.split("\n\n");
 
-    let values = chunks
-        .next()
-        .unwrap()
-        .split(':')
-        .nth(1)
-        .unwrap()
+    let seeds = chunks.next().unwrap();
+    let Some((_, seeds)) = seeds.split_once(':') else {
+        return Err(PuzzleError::missing(almanac, seeds, "expected `seeds:`"));
+    };
+    let values = seeds
         .split_whitespace()
-        .map(|x| x.parse::<i64>().unwrap())
-        .collect::<Vec<i64>>();
+        .map(|x| parse_token::<i64>(almanac, x))
+        .collect::<Result<Vec<i64>, PuzzleError>>()?;
 
-    return (values, chunks);
+    return Ok((values, chunks));
 }
 
-fn find_final_values(document: &str) -> Vec<i64> {
-    let (mut values, chunks) = split_almanac(document);
+pub fn find_final_values(document: &str) -> Result<Vec<i64>, PuzzleError> {
+    let (mut values, chunks) = split_almanac(document)?;
 
     for chunk in chunks {
-        let maps = read_maps(chunk);
+        let maps = read_maps(chunk).map_err(|err| err.within(document, chunk))?;
         values = values
             .iter()
             .map(|x| convert_through_maps(&maps, *x))
             .collect::<Vec<i64>>();
     }
 
-    return values;
+    return Ok(values);
 }
 
-fn convert_range_through_maps(maps: &Vec<(i64, i64, i64)>, range: (i64, i64)) -> Vec<(i64, i64)> {
+pub fn convert_range_through_maps(
+    maps: &Vec<(i64, i64, i64)>,
+    range: (i64, i64),
+) -> Vec<(i64, i64)> {
     let start = range.0;
     let end = range.1;
     let mut ranges: Vec<(i64, i64)> = Vec::new();
@@ -106,8 +114,15 @@ fn convert_range_through_maps(maps: &Vec<(i64, i64, i64)>, range: (i64, i64)) ->
     return ranges;
 }
 
-fn find_final_ranges(document: &str) -> Vec<(i64, i64)> {
-    let (values, chunks) = split_almanac(document);
+pub fn find_final_ranges(document: &str) -> Result<Vec<(i64, i64)>, PuzzleError> {
+    let (values, chunks) = split_almanac(document)?;
+    if values.len() % 2 != 0 {
+        return Err(PuzzleError::parse(
+            document,
+            document,
+            "expected seed ranges as pairs of numbers",
+        ));
+    }
 
     let mut ranges = values
         .chunks(2)
@@ -115,7 +130,7 @@ fn find_final_ranges(document: &str) -> Vec<(i64, i64)> {
         .collect::<Vec<(i64, i64)>>();
 
     for chunk in chunks {
-        let maps = read_maps(chunk);
+        let maps = read_maps(chunk).map_err(|err| err.within(document, chunk))?;
         let list_ranges = ranges
             .iter()
             .map(|x| convert_range_through_maps(&maps, *x))
@@ -127,23 +142,32 @@ fn find_final_ranges(document: &str) -> Vec<(i64, i64)> {
     }
 
     ranges.sort();
-    return ranges;
+    return Ok(ranges);
 }
 
 pub struct Day5Puzzle {}
-impl super::solve::Puzzle<String> for Day5Puzzle {
-    fn solve(&self, document: &str) -> String {
-        return find_final_values(document)
+impl super::solve::Puzzle for Day5Puzzle {
+    fn solve(&self, document: &str) -> Result<Answer, PuzzleError> {
+        return find_final_values(document)?
             .iter()
             .min()
-            .unwrap()
-            .to_string();
+            .map(|&x| x.into())
+            .ok_or_else(|| PuzzleError::unsolvable("no seeds"));
     }
 
-    fn solve2(&self, document: &str) -> String {
-        return find_final_ranges(document)[0].0.to_string();
+    fn solve2(&self, document: &str) -> Result<Answer, PuzzleError> {
+        return find_final_ranges(document)?
+            .first()
+            .map(|x| x.0.into())
+            .ok_or_else(|| PuzzleError::unsolvable("no seeds"));
     }
 }
+crate::register_puzzle!(
+    day: 5,
+    title: "If You Give A Seed A Fertilizer",
+    tags: ["ranges"],
+    puzzle: Day5Puzzle {},
+);
 
 #[cfg(test)]
 mod tests {
@@ -151,7 +175,8 @@ mod tests {
 
     #[test]
     fn test_read_single_map() {
-        assert_eq!(read_single_map(" 1  2  3 "), (2, 1, 3));
+        assert_eq!(read_single_map(" 1  2  3 "), Ok((2, 1, 3)));
+        assert!(read_single_map(" 1  2 ").is_err());
     }
 
     #[test]
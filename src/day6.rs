@@ -1,16 +1,18 @@
 use itertools::Itertools;
 
-fn extract_numbers_from_line(line: &str) -> Vec<i64> {
-    return line
-        .split(':')
-        .nth(1)
-        .unwrap()
+use crate::solve::{parse_token, Answer, PuzzleError};
+
+pub fn extract_numbers_from_line(line: &str) -> Result<Vec<i64>, PuzzleError> {
+    let Some((_, numbers)) = line.split_once(':') else {
+        return Err(PuzzleError::missing(line, line, "expected `:`"));
+    };
+    return numbers
         .split_whitespace()
-        .map(|x| x.parse::<i64>().unwrap())
-        .collect::<Vec<i64>>();
+        .map(|x| parse_token::<i64>(line, x))
+        .collect::<Result<Vec<i64>, PuzzleError>>();
 }
 
-fn get_time_range(time_limit: i64, distance: i64) -> (i64, i64) {
+pub fn get_time_range(time_limit: i64, distance: i64) -> (i64, i64) {
     // distance if time is T and time held is t, is t*(T-t)
     // so we require t*(T-t) > D
     // wolfram alpha gives
@@ -21,45 +23,70 @@ fn get_time_range(time_limit: i64, distance: i64) -> (i64, i64) {
     return (lower.floor() as i64 + 1, upper.ceil() as i64 - 1);
 }
 
-fn extract_long_number_from_line(line: &str) -> i64 {
-    return line
-        .split(':')
-        .nth(1)
-        .unwrap()
-        .split_whitespace()
-        .collect::<String>()
-        .parse::<i64>()
-        .unwrap();
+pub fn extract_long_number_from_line(line: &str) -> Result<i64, PuzzleError> {
+    let Some((_, numbers)) = line.split_once(':') else {
+        return Err(PuzzleError::missing(line, line, "expected `:`"));
+    };
+    let number = numbers.split_whitespace().collect::<String>();
+    return number.parse::<i64>().map_err(|err| {
+        PuzzleError::parse(
+            line,
+            numbers.trim_start(),
+            format!("invalid value `{}`: {}", number, err),
+        )
+    });
+}
+
+pub fn get_time_and_distance_lines(document: &str) -> Result<(&str, &str), PuzzleError> {
+    return document.lines().collect_tuple().ok_or_else(|| {
+        PuzzleError::parse(
+            document,
+            document,
+            "expected a time line and a distance line",
+        )
+    });
 }
 
 pub struct Day6Puzzle {}
-impl super::solve::Puzzle<String> for Day6Puzzle {
-    fn solve(&self, document: &str) -> String {
-        let (times, distances) = document
-            .lines()
-            .map(|line| extract_numbers_from_line(line))
-            .collect_tuple()
-            .unwrap();
+impl super::solve::Puzzle for Day6Puzzle {
+    fn solve(&self, document: &str) -> Result<Answer, PuzzleError> {
+        let (time_line, distance_line) = get_time_and_distance_lines(document)?;
+        let times =
+            extract_numbers_from_line(time_line).map_err(|err| err.within(document, time_line))?;
+        let distances = extract_numbers_from_line(distance_line)
+            .map_err(|err| err.within(document, distance_line))?;
+        if times.len() != distances.len() {
+            return Err(PuzzleError::parse(
+                document,
+                distance_line,
+                format!(
+                    "expected {} distances, found {}",
+                    times.len(),
+                    distances.len()
+                ),
+            ));
+        }
 
         let mut ans = 1;
         for (time, distance) in times.iter().zip(distances.iter()) {
             let (lower, upper) = get_time_range(*time, *distance);
             ans *= upper - lower + 1;
         }
-        return ans.to_string();
+        return Ok(ans.into());
     }
 
-    fn solve2(&self, document: &str) -> String {
-        let (time, distance) = document
-            .lines()
-            .map(|line| extract_long_number_from_line(line))
-            .collect_tuple()
-            .unwrap();
+    fn solve2(&self, document: &str) -> Result<Answer, PuzzleError> {
+        let (time_line, distance_line) = get_time_and_distance_lines(document)?;
+        let time = extract_long_number_from_line(time_line)
+            .map_err(|err| err.within(document, time_line))?;
+        let distance = extract_long_number_from_line(distance_line)
+            .map_err(|err| err.within(document, distance_line))?;
 
         let ans = get_time_range(time, distance);
-        return (ans.1 - ans.0 + 1).to_string();
+        return Ok((ans.1 - ans.0 + 1).into());
     }
 }
+crate::register_puzzle!(day: 6, title: "Wait For It", tags: ["math"], puzzle: Day6Puzzle {});
 
 #[cfg(test)]
 mod tests {
@@ -67,7 +94,11 @@ mod tests {
 
     #[test]
     fn test_extract_numbers_from_line() {
-        assert_eq!(extract_numbers_from_line("Time: 1  2  3"), vec![1, 2, 3]);
+        assert_eq!(
+            extract_numbers_from_line("Time: 1  2  3"),
+            Ok(vec![1, 2, 3])
+        );
+        assert!(extract_numbers_from_line("Time 1  2  3").is_err());
     }
 
     #[test]
@@ -77,6 +108,6 @@ mod tests {
 
     #[test]
     fn test_extract_long_number_from_line() {
-        assert_eq!(extract_long_number_from_line("Time: 1  2  3"), 123);
+        assert_eq!(extract_long_number_from_line("Time: 1  2  3"), Ok(123));
     }
 }
@@ -3,7 +3,9 @@ use std::collections::HashMap;
 use itertools::Itertools;
 use phf::phf_map;
 
-static CARDS: phf::Map<&'static str, i32> = phf_map! {
+use crate::solve::{parse_token, Answer, PuzzleError};
+
+pub static CARDS: phf::Map<&'static str, i32> = phf_map! {
     "2" => 2,
     "3" => 3,
     "4" => 4,
@@ -19,7 +21,7 @@ static CARDS: phf::Map<&'static str, i32> = phf_map! {
     "A" => 14,
 };
 
-fn get_hand_rank(hand: &str) -> (i32, i32, i32, i32, i32, i32) {
+pub fn get_hand_rank(hand: &str) -> (i32, i32, i32, i32, i32, i32) {
     let mut pairs: HashMap<String, i32> = HashMap::new();
     for card in hand.trim().chars() {
         pairs
@@ -52,19 +54,50 @@ fn get_hand_rank(hand: &str) -> (i32, i32, i32, i32, i32, i32) {
     );
 }
 
-fn get_hand_rank_and_bid(
+pub fn get_hand_rank_and_bid(
     line: &str,
     hand_ranker: &dyn Fn(&str) -> (i32, i32, i32, i32, i32, i32),
-) -> ((i32, i32, i32, i32, i32, i32), i32) {
+) -> Result<((i32, i32, i32, i32, i32, i32), i32), PuzzleError> {
     let mut parts = line.trim().split_whitespace();
-    let hand = parts.next().unwrap();
-    let bid = parts.next().unwrap();
+    let (Some(hand), Some(bid)) = (parts.next(), parts.next()) else {
+        return Err(PuzzleError::missing(
+            line,
+            line,
+            "expected a hand and a bid",
+        ));
+    };
+    if hand.len() != 5
+        || !hand
+            .chars()
+            .all(|card| CARDS.contains_key(&card.to_string()))
+    {
+        return Err(PuzzleError::parse(
+            line,
+            hand,
+            format!("invalid hand `{}`", hand),
+        ));
+    }
     let hand_rank = hand_ranker(hand);
-    let bid = bid.parse::<i32>().unwrap();
-    return (hand_rank, bid);
+    let bid = parse_token::<i32>(line, bid)?;
+    return Ok((hand_rank, bid));
 }
 
-fn get_hand_rank_wild(hand: &str) -> (i32, i32, i32, i32, i32, i32) {
+pub fn get_total_winnings(
+    document: &str,
+    hand_ranker: &dyn Fn(&str) -> (i32, i32, i32, i32, i32, i32),
+) -> Result<i32, PuzzleError> {
+    return Ok(document
+        .lines()
+        .map(|x| get_hand_rank_and_bid(x, hand_ranker).map_err(|err| err.within(document, x)))
+        .collect::<Result<Vec<_>, PuzzleError>>()?
+        .into_iter()
+        .sorted_unstable()
+        .enumerate()
+        .map(|(i, (_, bid))| (i as i32 + 1) * bid)
+        .sum::<i32>());
+}
+
+pub fn get_hand_rank_wild(hand: &str) -> (i32, i32, i32, i32, i32, i32) {
     let mut pairs: HashMap<String, i32> = HashMap::new();
     let mut wilds: i32 = 0;
     for card in hand.trim().chars() {
@@ -131,29 +164,16 @@ fn get_hand_rank_wild(hand: &str) -> (i32, i32, i32, i32, i32, i32) {
 }
 
 pub struct Day7Puzzle {}
-impl super::solve::Puzzle<String> for Day7Puzzle {
-    fn solve(&self, document: &str) -> String {
-        return document
-            .lines()
-            .map(|x| get_hand_rank_and_bid(x, &get_hand_rank))
-            .sorted_unstable()
-            .enumerate()
-            .map(|(i, (_, bid))| (i as i32 + 1) * bid)
-            .sum::<i32>()
-            .to_string();
+impl super::solve::Puzzle for Day7Puzzle {
+    fn solve(&self, document: &str) -> Result<Answer, PuzzleError> {
+        return Ok(get_total_winnings(document, &get_hand_rank)?.into());
     }
 
-    fn solve2(&self, document: &str) -> String {
-        return document
-            .lines()
-            .map(|x| get_hand_rank_and_bid(x, &get_hand_rank_wild))
-            .sorted_unstable()
-            .enumerate()
-            .map(|(i, (_, bid))| (i as i32 + 1) * bid)
-            .sum::<i32>()
-            .to_string();
+    fn solve2(&self, document: &str) -> Result<Answer, PuzzleError> {
+        return Ok(get_total_winnings(document, &get_hand_rank_wild)?.into());
     }
 }
+crate::register_puzzle!(day: 7, title: "Camel Cards", tags: ["sorting"], puzzle: Day7Puzzle {});
 
 #[cfg(test)]
 mod tests {
@@ -172,4 +192,18 @@ mod tests {
         assert_eq!(get_hand_rank_wild("AA3JJ"), (5, 14, 14, 3, 0, 0));
         assert_eq!(get_hand_rank_wild("57J85"), (3, 5, 7, 0, 8, 5));
     }
+
+    #[test]
+    fn test_get_hand_rank_and_bid() {
+        assert_eq!(
+            get_hand_rank_and_bid("AA3AA 12", &get_hand_rank),
+            Ok(((5, 14, 14, 3, 14, 14), 12))
+        );
+        assert_eq!(
+            get_hand_rank_and_bid("AA3A1 12", &get_hand_rank)
+                .unwrap_err()
+                .to_string(),
+            "parse error at line 1, column 1: invalid hand `AA3A1`"
+        );
+    }
 }
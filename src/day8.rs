@@ -1,54 +1,79 @@
 use num::integer::lcm;
-use std::collections::HashMap;
-
-fn get_single_node(node_info: &str) -> (&str, &str, &str) {
-    let node_name: &str = &node_info[0..3];
-    let left_node_name: &str = &node_info[7..10];
-    let right_node_name: &str = &node_info[12..15];
-    return (node_name, left_node_name, right_node_name);
+use std::collections::{HashMap, HashSet};
+
+use crate::solve::{Answer, PuzzleError};
+
+pub fn get_single_node(node_info: &str) -> Result<(&str, &str, &str), PuzzleError> {
+    let invalid = || PuzzleError::parse(node_info, node_info, "expected `AAA = (BBB, CCC)`");
+    let (node_name, targets) = node_info.split_once(" = ").ok_or_else(invalid)?;
+    let (left_node_name, right_node_name) = targets
+        .strip_prefix('(')
+        .and_then(|targets| targets.strip_suffix(')'))
+        .and_then(|targets| targets.split_once(", "))
+        .ok_or_else(invalid)?;
+    return Ok((node_name, left_node_name, right_node_name));
 }
 
-fn get_network(document: &str) -> HashMap<&str, (&str, &str)> {
+pub fn get_network(document: &str) -> Result<HashMap<&str, (&str, &str)>, PuzzleError> {
     let mut raw_node_map: HashMap<&str, (&str, &str)> = HashMap::new();
     for node_info in document.lines().skip(2) {
-        let (node_name, left_node_name, right_node_name) = get_single_node(node_info);
+        let (node_name, left_node_name, right_node_name) =
+            get_single_node(node_info).map_err(|err| err.within(document, node_info))?;
         raw_node_map.insert(node_name, (left_node_name, right_node_name));
     }
-    return raw_node_map;
+    return Ok(raw_node_map);
 }
 
 #[derive(Clone, Copy, Debug, PartialEq)]
-enum Direction {
+pub enum Direction {
     L,
     R,
 }
 
-fn take_step<'a>(
+pub fn take_step<'a>(
     current_node: &'a str,
     node_map: &HashMap<&'a str, (&'a str, &'a str)>,
     direction: Direction,
-) -> &'a str {
-    let (left_node_name, right_node_name) = node_map.get(current_node).unwrap();
+) -> Result<&'a str, PuzzleError> {
+    let Some((left_node_name, right_node_name)) = node_map.get(current_node) else {
+        return Err(PuzzleError::unsolvable(format!(
+            "node `{}` is not in the network",
+            current_node
+        )));
+    };
     match direction {
-        Direction::L => return *left_node_name,
-        Direction::R => return *right_node_name,
+        Direction::L => return Ok(*left_node_name),
+        Direction::R => return Ok(*right_node_name),
     }
 }
 
-fn get_direction_sequence<'a>(route: &'a str) -> impl Iterator<Item = (usize, Direction)> + 'a {
-    return route
-        .chars()
-        .map(|x| match x {
-            'L' => Direction::L,
-            'R' => Direction::R,
-            _ => panic!("Invalid direction"),
+pub fn get_direction_sequence<'a>(
+    route: &'a str,
+) -> Result<impl Iterator<Item = (usize, Direction)> + 'a, PuzzleError> {
+    let directions = route
+        .char_indices()
+        .map(|(i, x)| match x {
+            'L' => Ok(Direction::L),
+            'R' => Ok(Direction::R),
+            _ => Err(PuzzleError::parse(
+                route,
+                &route[i..],
+                format!("invalid direction `{}`", x),
+            )),
         })
-        .clone()
-        .cycle()
-        .enumerate();
+        .collect::<Result<Vec<Direction>, PuzzleError>>()?;
+    return Ok(directions.into_iter().cycle().enumerate());
+}
+
+pub fn get_route(document: &str) -> Result<&str, PuzzleError> {
+    let route = document.lines().nth(0).unwrap_or("");
+    if route.is_empty() {
+        return Err(PuzzleError::parse(document, route, "expected a route"));
+    }
+    return Ok(route);
 }
 
-fn get_starting_nodes<'a>(node_map: &'a HashMap<&'a str, (&'a str, &'a str)>) -> Vec<&'a str> {
+pub fn get_starting_nodes<'a>(node_map: &'a HashMap<&'a str, (&'a str, &'a str)>) -> Vec<&'a str> {
     return node_map
         .iter()
         .filter(|(node_name, (_, _))| node_name.ends_with("A"))
@@ -56,50 +81,75 @@ fn get_starting_nodes<'a>(node_map: &'a HashMap<&'a str, (&'a str, &'a str)>) ->
         .collect();
 }
 
-fn get_cycle_length(
+/// Counts the steps from `start_node` to the first node meeting `end_condition`. Being at the
+/// same node at the same point of a route of `route_length` directions twice means the walk
+/// goes round in circles and never gets there.
+pub fn get_cycle_length(
     start_node: &str,
     node_map: &HashMap<&str, (&str, &str)>,
     directions: impl Iterator<Item = (usize, Direction)>,
+    route_length: usize,
     end_condition: &dyn Fn(&str) -> bool,
-) -> usize {
+) -> Result<usize, PuzzleError> {
     let mut current_node = start_node;
+    let mut seen: HashSet<(&str, usize)> = HashSet::new();
     for (step, direction) in directions {
-        current_node = take_step(current_node, &node_map, direction);
+        if !seen.insert((current_node, step % route_length)) {
+            return Err(PuzzleError::unsolvable(format!(
+                "no node ending in `Z` can be reached from `{}`",
+                start_node
+            )));
+        }
+        current_node = take_step(current_node, &node_map, direction)?;
         if end_condition(current_node) {
-            return step + 1;
+            return Ok(step + 1);
         }
     }
     panic!("Unreachable");
 }
 
 pub struct Day8Puzzle {}
-impl super::solve::Puzzle<String> for Day8Puzzle {
-    fn solve(&self, document: &str) -> String {
-        let directions = get_direction_sequence(document.lines().nth(0).unwrap());
-        let node_map: HashMap<&str, (&str, &str)> = get_network(document);
-
-        return get_cycle_length("AAA", &node_map, directions, &|x| x.ends_with("Z")).to_string();
+impl super::solve::Puzzle for Day8Puzzle {
+    fn solve(&self, document: &str) -> Result<Answer, PuzzleError> {
+        let route = get_route(document)?;
+        let directions = get_direction_sequence(route)?;
+        let node_map: HashMap<&str, (&str, &str)> = get_network(document)?;
+
+        return Ok(
+            get_cycle_length("AAA", &node_map, directions, route.len(), &|x| {
+                x.ends_with("Z")
+            })?
+            .into(),
+        );
     }
 
-    fn solve2(&self, document: &str) -> String {
-        let node_map: HashMap<&str, (&str, &str)> = get_network(document);
+    fn solve2(&self, document: &str) -> Result<Answer, PuzzleError> {
+        let route = get_route(document)?;
+        let node_map: HashMap<&str, (&str, &str)> = get_network(document)?;
         let start_nodes = get_starting_nodes(&node_map);
 
-        return start_nodes
-            .iter()
-            .map(|x| {
-                get_cycle_length(
-                    x,
-                    &node_map,
-                    get_direction_sequence(document.lines().nth(0).unwrap()),
-                    &|x| x.ends_with("Z"),
-                ) as i128
-            })
-            .reduce(|x, y| lcm(x, y))
-            .unwrap()
-            .to_string();
+        let mut ans: Option<i128> = None;
+        for x in start_nodes {
+            let cycle_length = get_cycle_length(
+                x,
+                &node_map,
+                get_direction_sequence(route)?,
+                route.len(),
+                &|x| x.ends_with("Z"),
+            )? as i128;
+            ans = Some(ans.map_or(cycle_length, |ans| lcm(ans, cycle_length)));
+        }
+        return ans
+            .map(|ans| ans.into())
+            .ok_or_else(|| PuzzleError::unsolvable("no starting nodes ending in `A`"));
     }
 }
+crate::register_puzzle!(
+    day: 8,
+    title: "Haunted Wasteland",
+    tags: ["graph", "cycles", "math"],
+    puzzle: Day8Puzzle {},
+);
 
 #[cfg(test)]
 mod tests {
@@ -108,7 +158,7 @@ mod tests {
     #[test]
     fn test_get_single_node() {
         let node_info: &str = "AAA = (BBB, CCC)";
-        let (node_name, left_node_name, right_node_name) = get_single_node(node_info);
+        let (node_name, left_node_name, right_node_name) = get_single_node(node_info).unwrap();
         assert_eq!(node_name, "AAA");
         assert_eq!(left_node_name, "BBB");
         assert_eq!(right_node_name, "CCC");
@@ -120,18 +170,50 @@ mod tests {
         let mut node_map: HashMap<&str, (&str, &str)> = HashMap::new();
         node_map.insert("AAA", ("BBB", "CCC"));
 
-        assert_eq!(take_step(current_node, &node_map, Direction::L), "BBB");
+        assert_eq!(take_step(current_node, &node_map, Direction::L), Ok("BBB"));
+        assert!(take_step("BBB", &node_map, Direction::L).is_err());
     }
 
     #[test]
     fn test_get_direction_sequence() {
         let route = "LRL";
-        let mut direction_sequence = get_direction_sequence(route);
+        let mut direction_sequence = get_direction_sequence(route).unwrap();
         assert_eq!(direction_sequence.next().unwrap(), (0, Direction::L));
         assert_eq!(direction_sequence.next().unwrap(), (1, Direction::R));
         assert_eq!(direction_sequence.next().unwrap(), (2, Direction::L));
         assert_eq!(direction_sequence.next().unwrap(), (3, Direction::L));
         assert_eq!(direction_sequence.next().unwrap(), (4, Direction::R));
         assert_eq!(direction_sequence.next().unwrap(), (5, Direction::L));
+
+        assert!(get_direction_sequence("LRX").is_err());
+    }
+
+    #[test]
+    fn test_get_cycle_length() {
+        let document = "LL\n\nAAA = (BBB, BBB)\nBBB = (AAA, ZZZ)\nZZZ = (ZZZ, ZZZ)";
+        let node_map = get_network(document).unwrap();
+        let ends = |x: &str| x.ends_with("Z");
+        assert_eq!(
+            get_cycle_length(
+                "AAA",
+                &node_map,
+                get_direction_sequence("LL").unwrap(),
+                2,
+                &ends
+            )
+            .unwrap_err()
+            .to_string(),
+            "unsolvable input: no node ending in `Z` can be reached from `AAA`"
+        );
+        assert_eq!(
+            get_cycle_length(
+                "AAA",
+                &node_map,
+                get_direction_sequence("LR").unwrap(),
+                2,
+                &ends
+            ),
+            Ok(2)
+        );
     }
 }
@@ -1,6 +1,8 @@
 use itertools::Itertools;
 
-fn get_next_in_sequence(seq: Vec<i64>) -> i64 {
+use crate::solve::{parse_token, Answer, PuzzleError};
+
+pub fn get_next_in_sequence(seq: Vec<i64>) -> i64 {
     let mut ans = Vec::new();
     let mut cur_seq = seq;
 
@@ -17,7 +19,7 @@ fn get_next_in_sequence(seq: Vec<i64>) -> i64 {
     return ans.iter().sum();
 }
 
-fn get_previous_in_sequence(seq: Vec<i64>) -> i64 {
+pub fn get_previous_in_sequence(seq: Vec<i64>) -> i64 {
     let mut ans = Vec::new();
     let mut cur_seq = seq;
 
@@ -44,34 +46,41 @@ fn get_previous_in_sequence(seq: Vec<i64>) -> i64 {
         .sum();
 }
 
+pub fn get_sequences(document: &str) -> Result<Vec<Vec<i64>>, PuzzleError> {
+    return document
+        .lines()
+        .map(|x| {
+            x.split_whitespace()
+                .map(|y| parse_token::<i64>(document, y))
+                .collect::<Result<Vec<i64>, PuzzleError>>()
+        })
+        .collect();
+}
+
 pub struct Day9Puzzle {}
-impl super::solve::Puzzle<String> for Day9Puzzle {
-    fn solve(&self, document: &str) -> String {
-        return document
-            .lines()
-            .map(|x| {
-                x.split_whitespace()
-                    .map(|y| y.parse::<i64>().unwrap())
-                    .collect_vec()
-            })
+impl super::solve::Puzzle for Day9Puzzle {
+    fn solve(&self, document: &str) -> Result<Answer, PuzzleError> {
+        return Ok(get_sequences(document)?
+            .into_iter()
             .map(|x| get_next_in_sequence(x))
             .sum::<i64>()
-            .to_string();
+            .into());
     }
 
-    fn solve2(&self, document: &str) -> String {
-        return document
-            .lines()
-            .map(|x| {
-                x.split_whitespace()
-                    .map(|y| y.parse::<i64>().unwrap())
-                    .collect_vec()
-            })
+    fn solve2(&self, document: &str) -> Result<Answer, PuzzleError> {
+        return Ok(get_sequences(document)?
+            .into_iter()
             .map(|x| get_previous_in_sequence(x))
             .sum::<i64>()
-            .to_string();
+            .into());
     }
 }
+crate::register_puzzle!(
+    day: 9,
+    title: "Mirage Maintenance",
+    tags: ["sequences"],
+    puzzle: Day9Puzzle {},
+);
 
 #[cfg(test)]
 mod tests {
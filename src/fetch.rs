@@ -0,0 +1,70 @@
+use std::path::Path;
+
+use crate::client::Client;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Fetched {
+    /// The input was already on disk, so nothing was requested.
+    Cached,
+    Downloaded,
+}
+
+/// Downloads the input of `day` of `year` to `path` unless that file already exists. The input is
+/// written to a temporary file first, so an interrupted download never leaves a partial input
+/// behind.
+pub fn fetch_input(client: &Client, year: i32, day: i32, path: &Path) -> Result<Fetched, String> {
+    if path.exists() {
+        return Ok(Fetched::Cached);
+    }
+
+    let document = client.get(&format!("{}/input", client.day_url(year, day)))?;
+    if document.is_empty() {
+        return Err(format!("day {}: the server returned an empty input", day));
+    }
+
+    let write = || -> std::io::Result<()> {
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        let partial = path.with_extension("txt.part");
+        std::fs::write(&partial, &document)?;
+        return std::fs::rename(&partial, path);
+    };
+    write().map_err(|err| format!("{}: {}", path.display(), err))?;
+    return Ok(Fetched::Downloaded);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::mock_server;
+
+    #[test]
+    fn test_fetch_input() {
+        let dir = std::env::temp_dir().join(format!("aoc-fetch-test-{}", std::process::id()));
+        let path = dir.join("day5.txt");
+        let _ = std::fs::remove_dir_all(&dir);
+
+        let (url, server) = mock_server(vec![(200, "seeds: 1 2\n")]);
+        let client = Client::new(&url, Some("abc".to_string()));
+        assert_eq!(
+            fetch_input(&client, 2023, 5, &path),
+            Ok(Fetched::Downloaded)
+        );
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "seeds: 1 2\n");
+
+        // The server only answers once, so a second request would fail.
+        assert_eq!(fetch_input(&client, 2023, 5, &path), Ok(Fetched::Cached));
+        let requests = server.join().unwrap();
+        assert_eq!(requests.len(), 1);
+        assert!(requests[0].starts_with("GET /2023/day/5/input HTTP/1.1\r\n"));
+
+        let (url, server) = mock_server(vec![(500, "")]);
+        let client = Client::new(&url, Some("abc".to_string()));
+        assert!(fetch_input(&client, 2024, 6, &dir.join("day6.txt")).is_err());
+        assert!(!dir.join("day6.txt").exists());
+        server.join().unwrap();
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,267 @@
+use std::fmt;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+use crate::registry::DEFAULT_YEAR;
+
+/// Directory the puzzle inputs are read from unless [`INPUT_DIR_ENV`] is set.
+pub const DEFAULT_INPUT_DIR: &str = "input";
+
+/// Environment variable that overrides [`DEFAULT_INPUT_DIR`].
+pub const INPUT_DIR_ENV: &str = "AOC_INPUT_DIR";
+
+pub fn input_dir() -> PathBuf {
+    return match std::env::var_os(INPUT_DIR_ENV) {
+        Some(dir) if !dir.is_empty() => PathBuf::from(dir),
+        _ => PathBuf::from(DEFAULT_INPUT_DIR),
+    };
+}
+
+/// Directory the inputs of `year` are read from, the subdirectory of `input_dir` named after the
+/// year. Inputs of [`DEFAULT_YEAR`] are read from `input_dir` itself while it has no such
+/// subdirectory, since that is where they were kept before other years were supported.
+pub fn year_dir_in(input_dir: &Path, year: i32) -> PathBuf {
+    let dir = input_dir.join(year.to_string());
+    if year == DEFAULT_YEAR && !dir.is_dir() {
+        return input_dir.to_path_buf();
+    }
+    return dir;
+}
+
+/// The directory of `year` in the directory returned by [`input_dir`].
+pub fn year_dir(year: i32) -> PathBuf {
+    return year_dir_in(&input_dir(), year);
+}
+
+/// Which of a day's inputs to use. Examples are numbered from 1, so `Test(1)` is `dayNtest` and
+/// `Test(2)` is `dayNtest2`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum InputKind {
+    Real,
+    Test(u32),
+}
+impl InputKind {
+    pub fn file_stem(&self, day: i32) -> String {
+        return match self {
+            InputKind::Real => format!("day{}", day),
+            InputKind::Test(1) => format!("day{}test", day),
+            InputKind::Test(n) => format!("day{}test{}", day, n),
+        };
+    }
+
+    /// Parses a file stem such as `day5`, `day5test` or `day5test2` back into its day and kind.
+    pub fn from_file_stem(stem: &str) -> Option<(i32, InputKind)> {
+        let rest = stem.strip_prefix("day")?;
+        let digits = rest.len() - rest.trim_start_matches(|c: char| c.is_ascii_digit()).len();
+        let day = rest[..digits].parse::<i32>().ok()?;
+        let kind = match &rest[digits..] {
+            "" => InputKind::Real,
+            "test" => InputKind::Test(1),
+            suffix => match suffix.strip_prefix("test")?.parse::<u32>() {
+                Ok(n) if n >= 2 => InputKind::Test(n),
+                _ => return None,
+            },
+        };
+        return Some((day, kind));
+    }
+}
+impl fmt::Display for InputKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        return match self {
+            InputKind::Real => f.pad("real"),
+            InputKind::Test(1) => f.pad("test"),
+            InputKind::Test(n) => f.pad(&format!("test{}", n)),
+        };
+    }
+}
+
+/// The example inputs present in the input directory for `day` of `year`, in order. Always
+/// contains at least the first example, whether or not its file exists.
+pub fn example_kinds(year: i32, day: i32) -> Vec<InputKind> {
+    let mut kinds = vec![InputKind::Test(1)];
+    if let Ok(entries) = std::fs::read_dir(year_dir(year)) {
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if path.extension().is_none_or(|ext| ext != "txt") {
+                continue;
+            }
+            let Some(stem) = path.file_stem().and_then(|stem| stem.to_str()) else {
+                continue;
+            };
+            if let Some((d, kind @ InputKind::Test(n))) = InputKind::from_file_stem(stem) {
+                if d == day && n >= 2 {
+                    kinds.push(kind);
+                }
+            }
+        }
+    }
+    kinds.sort();
+    return kinds;
+}
+
+/// Where to read a puzzle input from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum InputSource {
+    /// A file in the input directory, named by its stem, such as `day5` or `day5test2`.
+    Named {
+        dir: PathBuf,
+        stem: String,
+    },
+    /// Any file.
+    Path(PathBuf),
+    Stdin,
+}
+impl InputSource {
+    /// A named input in the directory returned by [`year_dir`].
+    pub fn named(year: i32, stem: &str) -> InputSource {
+        return InputSource::Named {
+            dir: year_dir(year),
+            stem: stem.to_string(),
+        };
+    }
+
+    pub fn for_day(year: i32, day: i32, kind: InputKind) -> InputSource {
+        return InputSource::named(year, &kind.file_stem(day));
+    }
+
+    /// A file path, where `-` stands for stdin.
+    pub fn from_path(path: &Path) -> InputSource {
+        return match path.as_os_str() == "-" {
+            true => InputSource::Stdin,
+            false => InputSource::Path(path.to_path_buf()),
+        };
+    }
+
+    /// The file this source reads, if it is not stdin.
+    pub fn path(&self) -> Option<PathBuf> {
+        return match self {
+            InputSource::Named { dir, stem } => Some(dir.join(format!("{}.txt", stem))),
+            InputSource::Path(path) => Some(path.clone()),
+            InputSource::Stdin => None,
+        };
+    }
+
+    /// The kind of input the source holds, judging by its file name. Stdin and files not named
+    /// like a puzzle input count as real inputs.
+    pub fn kind(&self) -> InputKind {
+        let stem = match self {
+            InputSource::Named { stem, .. } => Some(stem.clone()),
+            InputSource::Path(path) => path
+                .file_stem()
+                .map(|stem| stem.to_string_lossy().into_owned()),
+            InputSource::Stdin => None,
+        };
+        return stem
+            .and_then(|stem| InputKind::from_file_stem(&stem))
+            .map_or(InputKind::Real, |(_, kind)| kind);
+    }
+
+    pub fn read(&self) -> Result<String, InputError> {
+        let mut document: String = String::new();
+        let result = match self.path() {
+            Some(path) => {
+                std::fs::File::open(&path).and_then(|mut f| f.read_to_string(&mut document))
+            }
+            None => std::io::stdin().read_to_string(&mut document),
+        };
+        return match result {
+            Ok(_) => Ok(document),
+            Err(error) => Err(InputError {
+                source: self.clone(),
+                error,
+            }),
+        };
+    }
+}
+impl fmt::Display for InputSource {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        return match self.path() {
+            Some(path) => write!(f, "{}", path.display()),
+            None => write!(f, "<stdin>"),
+        };
+    }
+}
+
+#[derive(Debug)]
+pub struct InputError {
+    pub source: InputSource,
+    pub error: std::io::Error,
+}
+impl InputError {
+    pub fn is_not_found(&self) -> bool {
+        return self.error.kind() == std::io::ErrorKind::NotFound;
+    }
+}
+impl fmt::Display for InputError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        return write!(f, "failed to read {}: {}", self.source, self.error);
+    }
+}
+impl std::error::Error for InputError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        return Some(&self.error);
+    }
+}
+
+/// Reads the input named `stem` from the directory of `year`.
+pub fn read_puzzle_input(year: i32, stem: &str) -> Result<String, InputError> {
+    return InputSource::named(year, stem).read();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_file_stem_round_trip() {
+        for kind in [InputKind::Real, InputKind::Test(1), InputKind::Test(12)] {
+            assert_eq!(
+                InputKind::from_file_stem(&kind.file_stem(5)),
+                Some((5, kind))
+            );
+        }
+        assert_eq!(InputKind::Test(2).file_stem(5), "day5test2");
+        assert_eq!(InputKind::from_file_stem("day5test1"), None);
+        assert_eq!(InputKind::from_file_stem("day5.answers"), None);
+        assert_eq!(InputKind::from_file_stem("dayx"), None);
+    }
+
+    #[test]
+    fn test_year_dir() {
+        let dir = std::env::temp_dir().join(format!("aoc-year-dir-test-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+
+        assert_eq!(year_dir_in(&dir, 2023), dir);
+        assert_eq!(year_dir_in(&dir, 2024), dir.join("2024"));
+        std::fs::create_dir(dir.join("2023")).unwrap();
+        assert_eq!(year_dir_in(&dir, 2023), dir.join("2023"));
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_input_source() {
+        let source = InputSource::Named {
+            dir: PathBuf::from("input"),
+            stem: "day5test2".to_string(),
+        };
+        assert_eq!(source.path(), Some(PathBuf::from("input/day5test2.txt")));
+        assert_eq!(InputSource::from_path(Path::new("-")), InputSource::Stdin);
+        assert_eq!(InputSource::Stdin.to_string(), "<stdin>");
+
+        assert_eq!(source.kind(), InputKind::Test(2));
+        assert_eq!(
+            InputSource::from_path(Path::new("inputs/day24test.txt")).kind(),
+            InputKind::Test(1)
+        );
+        assert_eq!(InputSource::Stdin.kind(), InputKind::Real);
+
+        let source = InputSource::Path(PathBuf::from("does/not/exist.txt"));
+        let err = source.read().unwrap_err();
+        assert!(err.is_not_found());
+        assert!(err
+            .to_string()
+            .starts_with("failed to read does/not/exist.txt: "));
+    }
+}
@@ -0,0 +1,54 @@
+//! Solutions to Advent of Code 2023, and to other years in `yYYYY` modules.
+//!
+//! Every day lives in its own `dayN` module and registers a [`solve::Puzzle`] with the
+//! [`registry`], so tools built on this crate can look puzzles up by year and day or call the
+//! helpers of a single day directly. Reading inputs, checking stored answers and benchmarking
+//! are shared by all days and exposed here as well.
+
+pub mod answers;
+pub mod bench;
+pub mod client;
+pub mod day1;
+pub mod day10;
+pub mod day11;
+pub mod day12;
+pub mod day13;
+pub mod day14;
+pub mod day15;
+pub mod day16;
+pub mod day17;
+pub mod day18;
+pub mod day19;
+pub mod day2;
+pub mod day20;
+pub mod day21;
+pub mod day22;
+pub mod day23;
+pub mod day24;
+pub mod day25;
+pub mod day3;
+pub mod day4;
+pub mod day5;
+pub mod day6;
+pub mod day7;
+pub mod day8;
+pub mod day9;
+pub mod fetch;
+pub mod input;
+pub mod memory;
+pub mod pool;
+pub mod registry;
+pub mod report;
+pub mod runner;
+pub mod scaffold;
+pub mod solve;
+pub mod submit;
+pub mod verify;
+pub mod visualize;
+pub mod watch;
+
+pub use registry::PuzzleInfo;
+pub use solve::{Answer, Puzzle, PuzzleError};
+
+#[doc(hidden)]
+pub use inventory;
@@ -1,28 +1,477 @@
-use solve::Puzzle;
-
-mod day1;
-mod day10;
-mod day11;
-mod day12;
-mod day13;
-mod day14;
-mod day15;
-mod day16;
-mod day17;
-mod day18;
-mod day19;
-mod day2;
-mod day20;
-mod day3;
-mod day4;
-mod day5;
-mod day6;
-mod day7;
-mod day8;
-mod day9;
-mod solve;
-
-fn main() {
+use std::process::ExitCode;
+use std::time::{Duration, Instant};
+
+use advent_of_code_2023::client::Client;
+use advent_of_code_2023::fetch::{self, Fetched};
+use advent_of_code_2023::input::{InputKind, InputSource};
+use advent_of_code_2023::report::{self, Record};
+use advent_of_code_2023::submit::{self, Attempt, Verdict};
+use advent_of_code_2023::watch::{self, Watcher};
+use advent_of_code_2023::{bench, input, pool, registry, runner, scaffold, verify};
+use advent_of_code_2023::{memory, visualize};
+use advent_of_code_2023::{Answer, Puzzle, PuzzleInfo};
+use clap::Parser;
+use cli::{
+    BenchAllArgs, BenchArgs, Cli, Command, FetchArgs, NewArgs, OutputFormat, PuzzleArgs,
+    ReportArgs, ReportFormat, RunAllArgs, SolveArgs, SubmitArgs, VerifyArgs, VisualizeArgs,
+    WatchArgs,
+};
+
+mod cli;
+
+#[cfg(feature = "count-allocations")]
+#[global_allocator]
+static ALLOCATOR: memory::CountingAllocator = memory::CountingAllocator;
+
+fn load_puzzle(year: i32, args: &PuzzleArgs) -> Result<(Box<dyn Puzzle>, String), String> {
+    let Some(info) = registry::find(year, args.day) else {
+        return Err(format!("day {} of {} has no solution yet", args.day, year));
+    };
+    let source = args.input_source(year);
+    let document = source.read().map_err(|err| err.to_string())?;
+    return Ok((info.puzzle_for(source.kind()), document));
+}
+
+/// Writes the report asked for by `args`, if any.
+fn write_report(args: &ReportArgs, records: &[Record]) -> Result<(), String> {
+    let report = match args.report {
+        Some(ReportFormat::Json) => report::format_json(records),
+        Some(ReportFormat::Csv) => report::format_csv(records),
+        None => return Ok(()),
+    };
+    return report::write_report(&report, args.report_file.as_deref());
+}
+
+fn run_solve(year: i32, args: &SolveArgs) -> ExitCode {
+    let (day, part, input) = (args.puzzle.day, args.puzzle.part, args.puzzle.input_name());
+    let solved = load_puzzle(year, &args.puzzle).and_then(|(puzzle, document)| {
+        let start = Instant::now();
+        let (answer, memory) =
+            memory::measure(|| runner::solve_part(puzzle.as_ref(), part, &document));
+        return Ok((
+            answer.map_err(|err| err.to_string())?,
+            start.elapsed(),
+            memory,
+        ));
+    });
+
+    let record = match &solved {
+        Ok((answer, duration, _)) => Record::solved(year, day, part, &input, answer, *duration),
+        Err(err) => Record::failed(year, day, part, &input, err),
+    };
+    if let Err(err) = write_report(&args.report, &[record]) {
+        eprintln!("error: {}", err);
+        return ExitCode::FAILURE;
+    }
+
+    let (answer, memory) = match solved {
+        Ok((answer, _, memory)) => (answer, memory),
+        Err(err) => {
+            eprintln!("error: {}", err);
+            return ExitCode::FAILURE;
+        }
+    };
+    if args.report.replaces_output() {
+        return ExitCode::SUCCESS;
+    }
+    match args.format {
+        OutputFormat::Plain => println!("{}", answer),
+        OutputFormat::Verbose => {
+            println!(
+                "Day {} part {} ({}): {}",
+                args.puzzle.day,
+                args.puzzle.part,
+                args.puzzle.input_source(year),
+                answer
+            );
+            if let Some(stats) = memory {
+                println!(
+                    "peak heap {}, allocated {} in {} allocations",
+                    memory::format_bytes(stats.peak),
+                    memory::format_bytes(stats.total),
+                    stats.count
+                );
+            }
+        }
+    }
+    return ExitCode::SUCCESS;
+}
+
+fn run_watch(year: i32, args: &WatchArgs) -> ExitCode {
+    let Some(info) = registry::find(year, args.puzzle.day) else {
+        eprintln!(
+            "error: day {} of {} has no solution yet",
+            args.puzzle.day, year
+        );
+        return ExitCode::FAILURE;
+    };
+    let source = args.puzzle.input_source(year);
+    let Some(path) = source.path() else {
+        eprintln!("error: cannot watch stdin");
+        return ExitCode::FAILURE;
+    };
+    let timeout = args.timeout();
+
+    // Panics are shown on the watch screen instead.
+    std::panic::set_hook(Box::new(|_| {}));
+    Watcher::new(&path).watch(args.interval, |path| {
+        print!("{}", watch::CLEAR_SCREEN);
+        match source.read() {
+            Ok(document) => {
+                let run = runner::run_document(
+                    info,
+                    args.puzzle.part,
+                    args.puzzle.input_kind(),
+                    document,
+                    timeout,
+                );
+                println!("{}", watch::format_run(&run, &source.to_string()));
+            }
+            Err(err) => println!("{}", err),
+        }
+        println!(
+            "\nwaiting for {} to change, press Ctrl-C to stop",
+            path.display()
+        );
+    });
+}
+
+fn run_visualize(year: i32, args: &VisualizeArgs) -> ExitCode {
+    let (puzzle, document) = match load_puzzle(year, &args.puzzle) {
+        Ok(loaded) => loaded,
+        Err(err) => {
+            eprintln!("error: {}", err);
+            return ExitCode::FAILURE;
+        }
+    };
+    let Some(visualization) = puzzle.visualization() else {
+        eprintln!("error: day {} has no visualization", args.puzzle.day);
+        return ExitCode::FAILURE;
+    };
+    let frames = match visualization.frames(&document, args.puzzle.part) {
+        Ok(frames) => frames,
+        Err(err) => {
+            eprintln!("error: {}", err);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let color = !args.no_color;
+    match args.step {
+        true => visualize::step(&frames, color, std::io::stdin().lock()),
+        false => visualize::animate(&frames, Duration::from_millis(args.delay), color),
+    }
+    return ExitCode::SUCCESS;
+}
+
+fn run_bench(year: i32, args: &BenchArgs) -> ExitCode {
+    let (puzzle, document) = match load_puzzle(year, &args.puzzle) {
+        Ok(loaded) => loaded,
+        Err(err) => {
+            eprintln!("error: {}", err);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let (answer, stats) = match bench::bench(
+        puzzle.as_ref(),
+        args.puzzle.part,
+        &document,
+        args.warmup as usize,
+        args.iterations as usize,
+    ) {
+        Ok(result) => result,
+        Err(err) => {
+            eprintln!("error: {}", err);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let record = Record::solved(
+        year,
+        args.puzzle.day,
+        args.puzzle.part,
+        &args.puzzle.input_name(),
+        &answer,
+        stats.median,
+    );
+    if let Err(err) = write_report(&args.report, &[record]) {
+        eprintln!("error: {}", err);
+        return ExitCode::FAILURE;
+    }
+    let quiet = args.report.replaces_output();
+    if !quiet {
+        println!(
+            "Day {} part {} ({}): {}",
+            args.puzzle.day,
+            args.puzzle.part,
+            args.puzzle.input_name(),
+            answer
+        );
+        println!("{} runs after {} warmup", args.iterations, args.warmup);
+    }
+
+    let key = (
+        year,
+        args.puzzle.day,
+        args.puzzle.part,
+        args.puzzle.input_name(),
+    );
+    let baseline = match &args.compare {
+        Some(path) => match bench::read_baseline(path) {
+            Ok(baseline) => baseline.get(&key).copied(),
+            Err(err) => {
+                eprintln!("error: {}", err);
+                return ExitCode::FAILURE;
+            }
+        },
+        None => None,
+    };
+    match baseline {
+        _ if quiet => {}
+        Some(baseline) => println!("{}", bench::format_comparison(&stats, &baseline)),
+        None => {
+            if let Some(path) = &args.compare {
+                println!("no baseline for this puzzle in {}", path.display());
+            }
+            println!("{}", bench::format_stats(&stats));
+        }
+    }
+
+    if let Some(path) = &args.save {
+        if let Err(err) = bench::save_baseline(path, key, stats) {
+            eprintln!("error: {}", err);
+            return ExitCode::FAILURE;
+        }
+        if !quiet {
+            println!("saved baseline to {}", path.display());
+        }
+    }
+    return ExitCode::SUCCESS;
+}
+
+fn run_bench_all(year: i32, args: &BenchAllArgs) -> ExitCode {
+    let runs = bench::bench_all(
+        &get_puzzles(year, &[]),
+        args.warmup as usize,
+        args.iterations as usize,
+        jobs(args.jobs),
+    );
+    let records: Vec<Record> = runs.iter().map(Record::from).collect();
+    if let Err(err) = write_report(&args.report, &records) {
+        eprintln!("error: {}", err);
+        return ExitCode::FAILURE;
+    }
+    let quiet = args.report.replaces_output();
+    if !quiet {
+        println!("{}", bench::format_bench_table(&runs));
+    }
+
+    if let Some(path) = &args.save {
+        for run in &runs {
+            let Ok((_, stats)) = run.result else {
+                continue;
+            };
+            let key = (run.year, run.day, run.part, run.input.to_string());
+            if let Err(err) = bench::save_baseline(path, key, stats) {
+                eprintln!("error: {}", err);
+                return ExitCode::FAILURE;
+            }
+        }
+        if !quiet {
+            println!("saved baseline to {}", path.display());
+        }
+    }
+    return ExitCode::SUCCESS;
+}
+
+/// The number of worker threads to use, where `0` means one per CPU.
+fn jobs(jobs: usize) -> usize {
+    return match jobs {
+        0 => pool::default_jobs(),
+        jobs => jobs,
+    };
+}
+
+fn get_puzzles(year: i32, days: &[i32]) -> Vec<&'static PuzzleInfo> {
+    return registry::puzzles(year)
+        .into_iter()
+        .filter(|info| days.is_empty() || days.contains(&info.day))
+        .collect();
+}
+
+fn run_list(year: i32) -> ExitCode {
+    let puzzles = registry::puzzles(year);
+    if puzzles.is_empty() {
+        let years: Vec<String> = registry::years().iter().map(i32::to_string).collect();
+        println!("no puzzles for {}, try one of {}", year, years.join(", "));
+    }
+    for info in puzzles {
+        println!(
+            "{:>2}  {:<32} {}",
+            info.day,
+            info.title,
+            info.tags.join(", ")
+        );
+    }
+    return ExitCode::SUCCESS;
+}
+
+fn run_all(year: i32, args: &RunAllArgs) -> ExitCode {
+    let puzzles = get_puzzles(year, &[]);
+    let runs = runner::run_all(&puzzles, args.run.timeout(), jobs(args.run.jobs));
+    let records: Vec<Record> = runs.iter().map(Record::from).collect();
+    if let Err(err) = write_report(&args.report, &records) {
+        eprintln!("error: {}", err);
+        return ExitCode::FAILURE;
+    }
+    if !args.report.replaces_output() {
+        println!("{}", runner::format_table(&runs));
+    }
+
+    if runs.iter().any(runner::PartRun::is_failure) {
+        return ExitCode::FAILURE;
+    }
+    return ExitCode::SUCCESS;
+}
+
+fn run_verify(year: i32, args: &VerifyArgs) -> ExitCode {
+    let runs = runner::run_all(
+        &get_puzzles(year, &args.days),
+        args.run.timeout(),
+        jobs(args.run.jobs),
+    );
+    let checks = match verify::verify(runs) {
+        Ok(checks) => checks,
+        Err(err) => {
+            eprintln!("error: {}", err);
+            return ExitCode::FAILURE;
+        }
+    };
+    println!("{}", verify::format_report(&checks));
+
+    if args.record {
+        match verify::record(&checks) {
+            Ok(recorded) => println!("recorded {} new answers", recorded),
+            Err(err) => {
+                eprintln!("error: {}", err);
+                return ExitCode::FAILURE;
+            }
+        }
+    }
+
+    if checks.iter().any(|check| check.is_failure()) {
+        return ExitCode::FAILURE;
+    }
+    return ExitCode::SUCCESS;
+}
+
+fn run_fetch(year: i32, args: &FetchArgs) -> ExitCode {
+    let client = match Client::from_env(args.server.base_url.as_deref()) {
+        Ok(client) => client,
+        Err(err) => {
+            eprintln!("error: {}", err);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let mut failed = false;
+    for &day in &args.days {
+        let path = InputSource::for_day(year, day, InputKind::Real)
+            .path()
+            .unwrap();
+        match fetch::fetch_input(&client, year, day, &path) {
+            Ok(Fetched::Downloaded) => println!("day {}: downloaded {}", day, path.display()),
+            Ok(Fetched::Cached) => println!("day {}: {} already exists", day, path.display()),
+            Err(err) => {
+                eprintln!("error: {}", err);
+                failed = true;
+            }
+        }
+    }
+    return match failed {
+        true => ExitCode::FAILURE,
+        false => ExitCode::SUCCESS,
+    };
+}
+
+fn get_submission(year: i32, args: &SubmitArgs) -> Result<Answer, String> {
+    if let Some(answer) = &args.answer {
+        return Ok(answer.parse().unwrap());
+    }
+    let Some(info) = registry::find(year, args.day) else {
+        return Err(format!("day {} of {} has no solution yet", args.day, year));
+    };
+    let document = InputSource::for_day(year, args.day, InputKind::Real)
+        .read()
+        .map_err(|err| err.to_string())?;
+    return runner::solve_part(info.puzzle().as_ref(), args.part, &document)
+        .map_err(|err| err.to_string());
+}
+
+fn run_submit(year: i32, args: &SubmitArgs) -> ExitCode {
+    let path = submit::history_path(year);
+    let submission = get_submission(year, args).and_then(|answer| {
+        let history = submit::read_history(&path)?;
+        submit::check_attempt(&history, args.day, args.part, &answer)
+            .map_err(|reason| format!("not submitting: {}", reason))?;
+        return Ok(answer);
+    });
+    let answer = match submission {
+        Ok(answer) => answer,
+        Err(err) => {
+            eprintln!("error: {}", err);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let verdict = Client::from_env(args.server.base_url.as_deref())
+        .and_then(|client| submit::submit(&client, year, args.day, args.part, &answer));
+    let verdict = match verdict {
+        Ok(verdict) => verdict,
+        Err(err) => {
+            eprintln!("error: {}", err);
+            return ExitCode::FAILURE;
+        }
+    };
+    println!(
+        "Day {} part {}: {}: {}",
+        args.day, args.part, answer, verdict
+    );
+
+    let attempt = Attempt {
+        time: submit::now(),
+        day: args.day,
+        part: args.part,
+        verdict,
+        answer,
+    };
+    if let Err(err) = submit::append_history(&path, &attempt) {
+        eprintln!("error: {}", err);
+        return ExitCode::FAILURE;
+    }
+    return match verdict {
+        Verdict::Correct => ExitCode::SUCCESS,
+        _ => ExitCode::FAILURE,
+    };
+}
+
+fn run_new(year: i32, args: &NewArgs) -> ExitCode {
+    return match scaffold::scaffold(&args.src, year, args.day, &args.title) {
+        Ok(written) => {
+            for path in written {
+                println!("wrote {}", path.display());
+            }
+            ExitCode::SUCCESS
+        }
+        Err(err) => {
+            eprintln!("error: {}", err);
+            ExitCode::FAILURE
+        }
+    };
+}
+
+fn run_interactive(year: i32) -> ExitCode {
     println!("Enter day number:");
     let mut day = String::new();
     std::io::stdin()
@@ -30,29 +479,7 @@ fn main() {
         .expect("Failed to read line");
     let day: i32 = day.trim().parse().expect("Please type a number!");
 
-    let puzzle: Box<dyn Puzzle<String>> = match day {
-        1 => Box::new(day1::Day1Puzzle {}),
-        2 => Box::new(day2::Day2Puzzle {}),
-        3 => Box::new(day3::Day3Puzzle {}),
-        4 => Box::new(day4::Day4Puzzle {}),
-        5 => Box::new(day5::Day5Puzzle {}),
-        6 => Box::new(day6::Day6Puzzle {}),
-        7 => Box::new(day7::Day7Puzzle {}),
-        8 => Box::new(day8::Day8Puzzle {}),
-        9 => Box::new(day9::Day9Puzzle {}),
-        10 => Box::new(day10::Day10Puzzle {}),
-        11 => Box::new(day11::Day11Puzzle {}),
-        12 => Box::new(day12::Day12Puzzle {}),
-        13 => Box::new(day13::Day13Puzzle {}),
-        14 => Box::new(day14::Day14Puzzle {}),
-        15 => Box::new(day15::Day15Puzzle {}),
-        16 => Box::new(day16::Day16Puzzle {}),
-        17 => Box::new(day17::Day17Puzzle {}),
-        18 => Box::new(day18::Day18Puzzle {}),
-        19 => Box::new(day19::Day19Puzzle {}),
-        20 => Box::new(day20::Day20Puzzle {}),
-        _ => panic!("Invalid day number"),
-    };
+    let info = registry::find(year, day).expect("Invalid day number");
 
     println!("Enter part number:");
     let mut part = String::new();
@@ -68,14 +495,51 @@ fn main() {
         .expect("Failed to read line");
     let test_case: bool = test_case.trim() == "y";
 
-    let document = match test_case {
-        true => solve::read_puzzle_input(&format!("day{}test", day)),
-        false => solve::read_puzzle_input(&format!("day{}", day)),
+    let kind = match test_case {
+        true => InputKind::Test(1),
+        false => InputKind::Real,
+    };
+    let puzzle = info.puzzle_for(kind);
+    let document = input::read_puzzle_input(year, &kind.file_stem(day));
+    let document = match document {
+        Ok(document) => document,
+        Err(err) => {
+            eprintln!("error: {}", err);
+            return ExitCode::FAILURE;
+        }
     };
     let answer = match part {
         1 => puzzle.solve(&document),
         2 => puzzle.solve2(&document),
         _ => panic!("Invalid part number"),
     };
-    println!("{}", answer);
+    return match answer {
+        Ok(answer) => {
+            println!("{}", answer);
+            ExitCode::SUCCESS
+        }
+        Err(err) => {
+            eprintln!("error: {}", err);
+            ExitCode::FAILURE
+        }
+    };
+}
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+    let year = cli.year;
+    return match cli.command {
+        Some(Command::Solve(args)) => run_solve(year, &args),
+        Some(Command::List) => run_list(year),
+        Some(Command::RunAll(args)) => run_all(year, &args),
+        Some(Command::Verify(args)) => run_verify(year, &args),
+        Some(Command::Watch(args)) => run_watch(year, &args),
+        Some(Command::Visualize(args)) => run_visualize(year, &args),
+        Some(Command::Bench(args)) => run_bench(year, &args),
+        Some(Command::BenchAll(args)) => run_bench_all(year, &args),
+        Some(Command::Fetch(args)) => run_fetch(year, &args),
+        Some(Command::Submit(args)) => run_submit(year, &args),
+        Some(Command::New(args)) => run_new(year, &args),
+        Some(Command::Interactive) | None => run_interactive(year),
+    };
 }
@@ -0,0 +1,166 @@
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::Cell;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// Heap usage of a piece of code on one thread.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct MemoryStats {
+    /// Most bytes allocated and not yet freed at any one time.
+    pub peak: usize,
+    /// Bytes allocated in total, including memory that was freed again.
+    pub total: usize,
+    /// Number of allocations, counting each reallocation as one.
+    pub count: usize,
+}
+
+/// Running totals for the current thread. Memory freed by another thread than the one that
+/// allocated it makes `current` drift, so it is signed.
+struct Counters {
+    current: Cell<isize>,
+    peak: Cell<isize>,
+    total: Cell<usize>,
+    count: Cell<usize>,
+}
+
+thread_local! {
+    // Const-initialized without a destructor, so reading it never allocates.
+    static COUNTERS: Counters = const {
+        Counters {
+            current: Cell::new(0),
+            peak: Cell::new(0),
+            total: Cell::new(0),
+            count: Cell::new(0),
+        }
+    };
+}
+
+static INSTALLED: AtomicBool = AtomicBool::new(false);
+
+fn record_alloc(size: usize) {
+    let _ = COUNTERS.try_with(|counters| {
+        let current = counters.current.get() + size as isize;
+        counters.current.set(current);
+        counters.peak.set(counters.peak.get().max(current));
+        counters.total.set(counters.total.get() + size);
+        counters.count.set(counters.count.get() + 1);
+    });
+}
+
+fn record_free(size: usize) {
+    let _ = COUNTERS.try_with(|counters| {
+        counters.current.set(counters.current.get() - size as isize);
+    });
+}
+
+/// The system allocator, counting what each thread allocates. Only counts anything once it is
+/// installed with `#[global_allocator]`, which the binary does when built with the
+/// `count-allocations` feature.
+pub struct CountingAllocator;
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        INSTALLED.store(true, Ordering::Relaxed);
+        let ptr = System.alloc(layout);
+        if !ptr.is_null() {
+            record_alloc(layout.size());
+        }
+        return ptr;
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        INSTALLED.store(true, Ordering::Relaxed);
+        let ptr = System.alloc_zeroed(layout);
+        if !ptr.is_null() {
+            record_alloc(layout.size());
+        }
+        return ptr;
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout);
+        record_free(layout.size());
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let new_ptr = System.realloc(ptr, layout, new_size);
+        if !new_ptr.is_null() {
+            record_free(layout.size());
+            record_alloc(new_size);
+        }
+        return new_ptr;
+    }
+}
+
+/// Whether [`CountingAllocator`] is the global allocator of this program.
+pub fn is_installed() -> bool {
+    return INSTALLED.load(Ordering::Relaxed);
+}
+
+/// Runs `f` and measures what it allocates on the current thread. Allocations made by threads it
+/// spawns are not counted. Returns no stats unless [`CountingAllocator`] is installed.
+pub fn measure<R, F: FnOnce() -> R>(f: F) -> (R, Option<MemoryStats>) {
+    let start = COUNTERS.with(|counters| {
+        counters.peak.set(counters.current.get());
+        return (
+            counters.current.get(),
+            counters.total.get(),
+            counters.count.get(),
+        );
+    });
+    let result = f();
+    let stats = COUNTERS.with(|counters| {
+        return MemoryStats {
+            peak: (counters.peak.get() - start.0).max(0) as usize,
+            total: counters.total.get() - start.1,
+            count: counters.count.get() - start.2,
+        };
+    });
+    return match is_installed() {
+        true => (result, Some(stats)),
+        false => (result, None),
+    };
+}
+
+pub fn format_bytes(bytes: usize) -> String {
+    return match bytes {
+        0..=1023 => format!("{}B", bytes),
+        1024..=1_048_575 => format!("{:.1}KiB", bytes as f64 / 1024.0),
+        1_048_576..=1_073_741_823 => format!("{:.1}MiB", bytes as f64 / 1_048_576.0),
+        _ => format!("{:.2}GiB", bytes as f64 / 1_073_741_824.0),
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[global_allocator]
+    static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+    #[test]
+    fn test_measure() {
+        let (sum, stats) = measure(|| {
+            let mut total = 0;
+            for i in 0..10 {
+                let v: Vec<u64> = vec![i; 1000];
+                total += v.iter().sum::<u64>();
+            }
+            return total;
+        });
+        let stats = stats.unwrap();
+        assert_eq!(sum, 45000);
+        assert_eq!(stats.count, 10);
+        assert_eq!(stats.total, 80000);
+        assert_eq!(stats.peak, 8000);
+
+        let (_, stats) = measure(|| 1 + 1);
+        assert_eq!(stats, Some(MemoryStats::default()));
+    }
+
+    #[test]
+    fn test_format_bytes() {
+        assert_eq!(format_bytes(512), "512B");
+        assert_eq!(format_bytes(1536), "1.5KiB");
+        assert_eq!(format_bytes(3 * 1_048_576), "3.0MiB");
+    }
+}
@@ -0,0 +1,65 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::thread;
+
+/// The number of jobs to use when asked for `0`, meaning as many as the machine can run at once.
+pub fn default_jobs() -> usize {
+    return thread::available_parallelism().map_or(1, |jobs| jobs.get());
+}
+
+/// Applies `task` to every item on up to `jobs` worker threads and returns the results in the
+/// order of `items`, however the work was interleaved. With a single job everything runs on the
+/// calling thread.
+pub fn map_ordered<T, R, F>(items: &[T], jobs: usize, task: F) -> Vec<R>
+where
+    T: Sync,
+    R: Send,
+    F: Fn(&T) -> R + Sync,
+{
+    let jobs = jobs.clamp(1, items.len().max(1));
+    if jobs == 1 {
+        return items.iter().map(task).collect();
+    }
+
+    let next = AtomicUsize::new(0);
+    let results: Mutex<Vec<Option<R>>> = Mutex::new(items.iter().map(|_| None).collect());
+    thread::scope(|scope| {
+        for _ in 0..jobs {
+            scope.spawn(|| loop {
+                let i = next.fetch_add(1, Ordering::Relaxed);
+                if i >= items.len() {
+                    return;
+                }
+                let result = task(&items[i]);
+                results.lock().unwrap()[i] = Some(result);
+            });
+        }
+    });
+    return results
+        .into_inner()
+        .unwrap()
+        .into_iter()
+        .map(|result| result.unwrap())
+        .collect();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn test_map_ordered() {
+        let items: Vec<u64> = (0..20).collect();
+        let task = |&i: &u64| {
+            // Later items finish first, so the results arrive out of order.
+            thread::sleep(Duration::from_millis(20 - i));
+            return i * i;
+        };
+        let expected: Vec<u64> = items.iter().map(|i| i * i).collect();
+        assert_eq!(map_ordered(&items, 1, task), expected);
+        assert_eq!(map_ordered(&items, 4, task), expected);
+        assert_eq!(map_ordered(&items, 100, task), expected);
+        assert!(map_ordered(&[] as &[u64], 4, task).is_empty());
+    }
+}
@@ -0,0 +1,137 @@
+use crate::input::InputKind;
+use crate::solve::Puzzle;
+
+/// The year of the puzzles registered without one, and of the commands run without `--year`.
+pub const DEFAULT_YEAR: i32 = 2023;
+
+/// A registered puzzle along with its metadata.
+///
+/// Each day module registers itself with [`register_puzzle!`](crate::register_puzzle), so new days are picked up by every
+/// command without being listed anywhere else.
+pub struct PuzzleInfo {
+    pub year: i32,
+    pub day: i32,
+    pub title: &'static str,
+    pub tags: &'static [&'static str],
+    pub build: fn() -> Box<dyn Puzzle>,
+    /// Builds the puzzle for the example inputs, for puzzles whose examples use other settings
+    /// than the real input, such as a smaller area.
+    pub build_example: Option<fn() -> Box<dyn Puzzle>>,
+}
+impl PuzzleInfo {
+    pub fn puzzle(&self) -> Box<dyn Puzzle> {
+        return (self.build)();
+    }
+
+    /// The puzzle set up for an input of the given kind.
+    pub fn puzzle_for(&self, kind: InputKind) -> Box<dyn Puzzle> {
+        return match (kind, self.build_example) {
+            (InputKind::Test(_), Some(build_example)) => build_example(),
+            _ => self.puzzle(),
+        };
+    }
+
+    /// The parts the puzzle has, `[1, 2]` or just `[1]`.
+    pub fn parts(&self) -> Vec<i32> {
+        return match self.puzzle().has_part2() {
+            true => vec![1, 2],
+            false => vec![1],
+        };
+    }
+}
+
+inventory::collect!(PuzzleInfo);
+
+/// Registers a puzzle for a day, of [`DEFAULT_YEAR`] unless a year is given:
+///
+/// ```ignore
+/// register_puzzle!(day: 1, title: "Trebuchet?!", tags: ["parsing"], puzzle: Day1Puzzle {});
+/// register_puzzle!(year: 2024, day: 1, title: "Historian Hysteria", tags: [], puzzle: Day1Puzzle {});
+/// ```
+///
+/// A puzzle whose examples need other settings than the real input can also give the puzzle to
+/// use for them, as `example: Day24Puzzle { test_area: EXAMPLE_AREA }` after `puzzle`.
+#[macro_export]
+macro_rules! register_puzzle {
+    (day: $day:expr, $($rest:tt)*) => {
+        $crate::register_puzzle!(year: $crate::registry::DEFAULT_YEAR, day: $day, $($rest)*);
+    };
+    (year: $year:expr, day: $day:expr, title: $title:expr, tags: [$($tag:expr),* $(,)?], puzzle: $puzzle:expr $(, example: $example:expr)? $(,)?) => {
+        $crate::inventory::submit! {
+            $crate::registry::PuzzleInfo {
+                year: $year,
+                day: $day,
+                title: $title,
+                tags: &[$($tag),*],
+                build: || Box::new($puzzle),
+                build_example: $crate::register_puzzle!(@example $($example)?),
+            }
+        }
+    };
+    (@example) => {
+        None
+    };
+    (@example $example:expr) => {
+        Some(|| -> Box<dyn $crate::solve::Puzzle> { Box::new($example) })
+    };
+}
+
+/// All registered puzzles of `year`, ordered by day.
+pub fn puzzles(year: i32) -> Vec<&'static PuzzleInfo> {
+    let mut puzzles: Vec<&'static PuzzleInfo> = inventory::iter::<PuzzleInfo>()
+        .filter(|info| info.year == year)
+        .collect();
+    puzzles.sort_by_key(|info| info.day);
+    return puzzles;
+}
+
+/// The years that have registered puzzles, in order.
+pub fn years() -> Vec<i32> {
+    let mut years: Vec<i32> = inventory::iter::<PuzzleInfo>()
+        .map(|info| info.year)
+        .collect();
+    years.sort();
+    years.dedup();
+    return years;
+}
+
+pub fn find(year: i32, day: i32) -> Option<&'static PuzzleInfo> {
+    return inventory::iter::<PuzzleInfo>().find(|info| info.year == year && info.day == day);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_puzzles() {
+        let days: Vec<i32> = puzzles(2023).iter().map(|info| info.day).collect();
+        assert!(days.windows(2).all(|pair| pair[0] < pair[1]));
+        assert!((1..=20).all(|day| days.contains(&day)));
+        assert!(puzzles(2015).is_empty());
+        assert!(years().contains(&2023));
+    }
+
+    #[test]
+    fn test_find() {
+        assert_eq!(find(2023, 7).unwrap().title, "Camel Cards");
+        assert_eq!(find(2023, 7).unwrap().year, 2023);
+        assert!(find(2023, 0).is_none());
+        assert!(find(2015, 7).is_none());
+    }
+
+    #[test]
+    fn test_parts() {
+        assert_eq!(find(2023, 7).unwrap().parts(), vec![1, 2]);
+        assert_eq!(find(2023, 25).unwrap().parts(), vec![1]);
+    }
+
+    #[test]
+    fn test_puzzle_for() {
+        let info = find(2023, 24).unwrap();
+        let example = "19, 13, 30 @ -2, 1, -2\n18, 19, 22 @ -1, -1, -2\n20, 25, 34 @ -2, -2, -4";
+        let answer = |kind: InputKind| info.puzzle_for(kind).solve(example).unwrap().to_string();
+        assert_eq!(answer(InputKind::Test(1)), "2");
+        assert_eq!(answer(InputKind::Real), "0");
+    }
+}
@@ -0,0 +1,310 @@
+use std::path::Path;
+use std::time::Duration;
+
+use crate::bench::BenchRun;
+use crate::runner::{PartRun, Status};
+use crate::solve::Answer;
+
+/// One row of a report: how a single part went on a single input.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Record {
+    pub year: i32,
+    pub day: i32,
+    pub part: i32,
+    /// `real`, `test`, `testN`, or the path of an input given on the command line.
+    pub input: String,
+    pub answer: Option<Answer>,
+    /// How long the part took, or the median of the measured runs for a benchmark.
+    pub duration: Option<Duration>,
+    pub status: String,
+    pub error: Option<String>,
+}
+impl From<&PartRun> for Record {
+    fn from(run: &PartRun) -> Record {
+        let (status, error) = match run.status {
+            Status::TimedOut(_) => ("timed out".to_string(), Some(run.status.to_string())),
+            status => (status.to_string(), run.error.clone()),
+        };
+        return Record {
+            year: run.year,
+            day: run.day,
+            part: run.part,
+            input: run.input.to_string(),
+            answer: run.answer.clone(),
+            duration: match run.status {
+                Status::MissingInput => None,
+                _ => Some(run.duration),
+            },
+            status,
+            error,
+        };
+    }
+}
+impl From<&BenchRun> for Record {
+    fn from(run: &BenchRun) -> Record {
+        let (answer, duration, status, error) = match &run.result {
+            Ok((answer, stats)) => (Some(answer.clone()), Some(stats.median), Status::Ok, None),
+            Err(err) => {
+                // Benchmarks describe failures as text; report them with the runner's statuses.
+                let (status, error) = if *err == Status::MissingInput.to_string() {
+                    (Status::MissingInput, None)
+                } else if let Some(message) = err.strip_prefix("panicked: ") {
+                    (Status::Panicked, Some(message.to_string()))
+                } else {
+                    let message = err.strip_prefix("error: ").unwrap_or(err);
+                    (Status::Error, Some(message.to_string()))
+                };
+                (None, None, status, error)
+            }
+        };
+        return Record {
+            year: run.year,
+            day: run.day,
+            part: run.part,
+            input: run.input.to_string(),
+            answer,
+            duration,
+            status: status.to_string(),
+            error,
+        };
+    }
+}
+impl Record {
+    /// A record of a part solved outside the runner, such as by the `solve` command.
+    pub fn solved(
+        year: i32,
+        day: i32,
+        part: i32,
+        input: &str,
+        answer: &Answer,
+        duration: Duration,
+    ) -> Record {
+        return Record {
+            year,
+            day,
+            part,
+            input: input.to_string(),
+            answer: Some(answer.clone()),
+            duration: Some(duration),
+            status: Status::Ok.to_string(),
+            error: None,
+        };
+    }
+
+    /// A record of a part that failed outside the runner.
+    pub fn failed(year: i32, day: i32, part: i32, input: &str, error: &str) -> Record {
+        return Record {
+            year,
+            day,
+            part,
+            input: input.to_string(),
+            answer: None,
+            duration: None,
+            status: Status::Error.to_string(),
+            error: Some(error.to_string()),
+        };
+    }
+}
+
+/// Quotes `text` as a JSON string.
+fn json_string(text: &str) -> String {
+    let mut quoted = String::from("\"");
+    for c in text.chars() {
+        match c {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            '\r' => quoted.push_str("\\r"),
+            '\t' => quoted.push_str("\\t"),
+            c if c.is_control() => quoted.push_str(&format!("\\u{:04x}", c as u32)),
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    return quoted;
+}
+
+/// Integer answers become JSON numbers and text answers become strings.
+fn json_answer(answer: &Answer) -> String {
+    return match answer {
+        Answer::Text(text) => json_string(text),
+        answer => answer.to_string(),
+    };
+}
+
+/// Formats the records as a JSON array with one object per record. Durations are in seconds, and
+/// missing values are `null`.
+pub fn format_json(records: &[Record]) -> String {
+    let null = || "null".to_string();
+    let objects: Vec<String> = records
+        .iter()
+        .map(|record| {
+            let fields = [
+                ("year", record.year.to_string()),
+                ("day", record.day.to_string()),
+                ("part", record.part.to_string()),
+                ("input", json_string(&record.input)),
+                (
+                    "answer",
+                    record.answer.as_ref().map_or_else(null, json_answer),
+                ),
+                (
+                    "duration",
+                    record
+                        .duration
+                        .map_or_else(null, |duration| duration.as_secs_f64().to_string()),
+                ),
+                ("status", json_string(&record.status)),
+                (
+                    "error",
+                    record.error.as_deref().map_or_else(null, json_string),
+                ),
+            ];
+            let fields: Vec<String> = fields
+                .iter()
+                .map(|(name, value)| format!("\"{}\": {}", name, value))
+                .collect();
+            return format!("  {{{}}}", fields.join(", "));
+        })
+        .collect();
+    if objects.is_empty() {
+        return "[]".to_string();
+    }
+    return format!("[\n{}\n]", objects.join(",\n"));
+}
+
+/// Quotes a CSV field if it contains a separator, quote or line break.
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        return format!("\"{}\"", field.replace('"', "\"\""));
+    }
+    return field.to_string();
+}
+
+/// Formats the records as CSV with a header line. Durations are in seconds, and missing values are
+/// empty fields.
+pub fn format_csv(records: &[Record]) -> String {
+    let mut lines = vec!["year,day,part,input,answer,duration,status,error".to_string()];
+    for record in records {
+        let fields = [
+            record.year.to_string(),
+            record.day.to_string(),
+            record.part.to_string(),
+            record.input.clone(),
+            record
+                .answer
+                .as_ref()
+                .map_or(String::new(), Answer::to_string),
+            record
+                .duration
+                .map_or(String::new(), |duration| duration.as_secs_f64().to_string()),
+            record.status.clone(),
+            record.error.clone().unwrap_or_default(),
+        ];
+        let fields: Vec<String> = fields.iter().map(|field| csv_field(field)).collect();
+        lines.push(fields.join(","));
+    }
+    return lines.join("\n");
+}
+
+/// Writes a formatted report to `path`, or to stdout if there is no path.
+pub fn write_report(report: &str, path: Option<&Path>) -> Result<(), String> {
+    return match path {
+        Some(path) => std::fs::write(path, format!("{}\n", report))
+            .map_err(|err| format!("{}: {}", path.display(), err)),
+        None => {
+            println!("{}", report);
+            Ok(())
+        }
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bench::Stats;
+    use crate::input::InputKind;
+
+    fn records() -> Vec<Record> {
+        let ok = PartRun {
+            year: 2023,
+            day: 1,
+            part: 2,
+            input: InputKind::Test(2),
+            answer: Some(Answer::from(281)),
+            duration: Duration::from_millis(1500),
+            status: Status::Ok,
+            error: None,
+            memory: None,
+        };
+        let failed = Record::failed(2023, 7, 1, "input/day7.txt", "invalid card, \"Z\"");
+        return vec![Record::from(&ok), failed];
+    }
+
+    #[test]
+    fn test_format_json() {
+        assert_eq!(
+            format_json(&records()),
+            [
+                "[",
+                "  {\"year\": 2023, \"day\": 1, \"part\": 2, \"input\": \"test2\", \"answer\": 281, \
+                 \"duration\": 1.5, \"status\": \"ok\", \"error\": null},",
+                "  {\"year\": 2023, \"day\": 7, \"part\": 1, \"input\": \"input/day7.txt\", \"answer\": null, \
+                 \"duration\": null, \"status\": \"error\", \
+                 \"error\": \"invalid card, \\\"Z\\\"\"}",
+                "]",
+            ]
+            .join("\n")
+        );
+        assert_eq!(format_json(&[]), "[]");
+        assert_eq!(json_answer(&Answer::from("a\tb")), "\"a\\tb\"");
+    }
+
+    #[test]
+    fn test_from_bench_run() {
+        let bench_run = |result: Result<(Answer, Stats), String>| {
+            return Record::from(&BenchRun {
+                year: 2023,
+                day: 6,
+                part: 2,
+                input: InputKind::Real,
+                result,
+            });
+        };
+        let missing = PartRun {
+            year: 2023,
+            day: 6,
+            part: 2,
+            input: InputKind::Real,
+            answer: None,
+            duration: Duration::ZERO,
+            status: Status::MissingInput,
+            error: None,
+            memory: None,
+        };
+        assert_eq!(
+            bench_run(Err("missing input".to_string())),
+            Record::from(&missing)
+        );
+
+        let record = bench_run(Err("panicked: index out of bounds".to_string()));
+        assert_eq!(record.status, "panicked");
+        assert_eq!(record.error.as_deref(), Some("index out of bounds"));
+        let record = bench_run(Err("error: unsolvable input: no loop".to_string()));
+        assert_eq!(record.status, "error");
+        assert_eq!(record.error.as_deref(), Some("unsolvable input: no loop"));
+    }
+
+    #[test]
+    fn test_format_csv() {
+        assert_eq!(
+            format_csv(&records()),
+            [
+                "year,day,part,input,answer,duration,status,error",
+                "2023,1,2,test2,281,1.5,ok,",
+                "2023,7,1,input/day7.txt,,,error,\"invalid card, \"\"Z\"\"\"",
+            ]
+            .join("\n")
+        );
+    }
+}
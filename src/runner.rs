@@ -0,0 +1,452 @@
+use std::any::Any;
+use std::fmt;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::input::{self, InputKind, InputSource};
+use crate::memory::{self, MemoryStats};
+use crate::pool;
+use crate::registry::PuzzleInfo;
+use crate::solve::{Answer, Puzzle, PuzzleError};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Status {
+    Ok,
+    Error,
+    Panicked,
+    /// The part was still running when its time ran out.
+    TimedOut(Duration),
+    MissingInput,
+}
+impl fmt::Display for Status {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        return match self {
+            Status::Ok => f.pad("ok"),
+            Status::Error => f.pad("error"),
+            Status::Panicked => f.pad("panicked"),
+            Status::TimedOut(timeout) => {
+                f.pad(&format!("timed out after {}s", timeout.as_secs_f64()))
+            }
+            Status::MissingInput => f.pad("missing input"),
+        };
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct PartRun {
+    pub year: i32,
+    pub day: i32,
+    pub part: i32,
+    pub input: InputKind,
+    pub answer: Option<Answer>,
+    pub duration: Duration,
+    pub status: Status,
+    /// The error returned by the puzzle, or the reason its input could not be read, when the
+    /// status is [`Status::Error`], and the panic message when it is [`Status::Panicked`].
+    pub error: Option<String>,
+    /// What the part allocated, if it finished and allocations are being counted.
+    pub memory: Option<MemoryStats>,
+}
+impl PartRun {
+    /// Whether the run should fail a check of the whole repository: the part panicked or timed
+    /// out, or returned an error on a real input. Errors on example inputs are not failures,
+    /// since many examples only apply to one of the two parts.
+    pub fn is_failure(&self) -> bool {
+        return match self.status {
+            Status::Panicked | Status::TimedOut(_) => true,
+            Status::Error => self.input == InputKind::Real,
+            Status::Ok | Status::MissingInput => false,
+        };
+    }
+}
+
+pub fn solve_part(puzzle: &dyn Puzzle, part: i32, document: &str) -> Result<Answer, PuzzleError> {
+    return match part {
+        1 => puzzle.solve(document),
+        2 => puzzle.solve2(document),
+        _ => panic!("Invalid part number"),
+    };
+}
+
+pub(crate) fn panic_message(payload: &(dyn Any + Send)) -> String {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        return message.to_string();
+    }
+    if let Some(message) = payload.downcast_ref::<String>() {
+        return message.clone();
+    }
+    return "unknown panic payload".to_string();
+}
+
+/// Solves `run.part` of the puzzle on its own thread and records the outcome in `run`. A panic is
+/// caught and recorded with its message. If `timeout` elapses first, the run is recorded as timed
+/// out and the thread is left to finish in the background, since threads cannot be stopped.
+fn run_isolated(
+    run: &mut PartRun,
+    info: &'static PuzzleInfo,
+    document: String,
+    timeout: Option<Duration>,
+) {
+    let (part, input) = (run.part, run.input);
+    let (sender, receiver) = mpsc::channel();
+    let spawned = thread::Builder::new()
+        .name(format!("day{}-part{}", info.day, part))
+        .spawn(move || {
+            let puzzle = info.puzzle_for(input);
+            let start = Instant::now();
+            let (result, memory) = memory::measure(|| {
+                return panic::catch_unwind(AssertUnwindSafe(|| {
+                    solve_part(puzzle.as_ref(), part, &document)
+                }));
+            });
+            let _ = sender.send((result, start.elapsed(), memory));
+        });
+    if let Err(err) = spawned {
+        run.error = Some(format!("failed to start a thread: {}", err));
+        run.status = Status::Error;
+        return;
+    }
+
+    let received = match timeout {
+        Some(timeout) => receiver.recv_timeout(timeout),
+        None => receiver.recv().map_err(|_| RecvTimeoutError::Disconnected),
+    };
+    match received {
+        Ok((Ok(Ok(answer)), duration, memory)) => {
+            run.duration = duration;
+            run.memory = memory;
+            run.answer = Some(answer);
+            run.status = Status::Ok;
+        }
+        Ok((Ok(Err(err)), duration, memory)) => {
+            run.duration = duration;
+            run.memory = memory;
+            run.error = Some(err.to_string());
+            run.status = Status::Error;
+        }
+        Ok((Err(payload), duration, _)) => {
+            run.duration = duration;
+            run.error = Some(panic_message(payload.as_ref()));
+            run.status = Status::Panicked;
+        }
+        Err(RecvTimeoutError::Timeout) => {
+            run.duration = timeout.unwrap();
+            run.status = Status::TimedOut(timeout.unwrap());
+        }
+        Err(RecvTimeoutError::Disconnected) => {
+            run.error = Some("the puzzle thread exited without an answer".to_string());
+            run.status = Status::Panicked;
+        }
+    }
+}
+
+/// Runs one part of a puzzle against one of its inputs in isolation, so that a panic or a part
+/// running past `timeout` is reported and the caller can carry on with the next part.
+pub fn run_part(
+    info: &'static PuzzleInfo,
+    part: i32,
+    input: InputKind,
+    timeout: Option<Duration>,
+) -> PartRun {
+    let document = match InputSource::for_day(info.year, info.day, input).read() {
+        Ok(document) => document,
+        Err(err) => {
+            let mut run = new_run(info, part, input);
+            if !err.is_not_found() {
+                run.error = Some(err.to_string());
+                run.status = Status::Error;
+            }
+            return run;
+        }
+    };
+    return run_document(info, part, input, document, timeout);
+}
+
+/// Like [`run_part`], for an input that was already read.
+pub fn run_document(
+    info: &'static PuzzleInfo,
+    part: i32,
+    input: InputKind,
+    document: String,
+    timeout: Option<Duration>,
+) -> PartRun {
+    let mut run = new_run(info, part, input);
+    run_isolated(&mut run, info, document, timeout);
+    return run;
+}
+
+fn new_run(info: &'static PuzzleInfo, part: i32, input: InputKind) -> PartRun {
+    return PartRun {
+        year: info.year,
+        day: info.day,
+        part,
+        input,
+        answer: None,
+        duration: Duration::ZERO,
+        status: Status::MissingInput,
+        error: None,
+        memory: None,
+    };
+}
+
+/// Runs both parts of every given puzzle on each of its example inputs and its real input, giving
+/// each part at most `timeout` and running up to `jobs` parts at once. The runs are returned in
+/// the same order whatever the number of jobs. Panic messages are silenced while the puzzles run;
+/// they show up as a `panicked` status instead.
+pub fn run_all(
+    puzzles: &[&'static PuzzleInfo],
+    timeout: Option<Duration>,
+    jobs: usize,
+) -> Vec<PartRun> {
+    let mut tasks: Vec<(&'static PuzzleInfo, InputKind, i32)> = Vec::new();
+    for &info in puzzles {
+        let mut inputs = input::example_kinds(info.year, info.day);
+        inputs.push(InputKind::Real);
+        for input in inputs {
+            for part in info.parts() {
+                tasks.push((info, input, part));
+            }
+        }
+    }
+
+    let hook = panic::take_hook();
+    panic::set_hook(Box::new(|_| {}));
+    let runs = pool::map_ordered(&tasks, jobs, |&(info, input, part)| {
+        return run_part(info, part, input, timeout);
+    });
+    panic::set_hook(hook);
+    return runs;
+}
+
+pub fn format_duration(duration: Duration) -> String {
+    let nanos = duration.as_nanos();
+    return match nanos {
+        0..=999 => format!("{}ns", nanos),
+        1_000..=999_999 => format!("{}µs", nanos / 1_000),
+        1_000_000..=999_999_999 => format!("{:.2}ms", nanos as f64 / 1_000_000.0),
+        _ => format!("{:.2}s", nanos as f64 / 1_000_000_000.0),
+    };
+}
+
+/// Formats the runs as a table, with columns for the memory used when any run has memory stats.
+pub fn format_table(runs: &[PartRun]) -> String {
+    let with_memory = runs.iter().any(|run| run.memory.is_some());
+    let mut header = vec!["Day", "Part", "Input", "Answer", "Time"];
+    if with_memory {
+        header.extend(["Peak", "Allocated", "Allocs"]);
+    }
+    header.push("Status");
+
+    let rows: Vec<Vec<String>> = runs
+        .iter()
+        .map(|run| {
+            let mut row = vec![
+                run.day.to_string(),
+                run.part.to_string(),
+                run.input.to_string(),
+                run.answer.as_ref().map_or(String::new(), Answer::to_string),
+                match run.status {
+                    Status::MissingInput => String::new(),
+                    _ => format_duration(run.duration),
+                },
+            ];
+            if with_memory {
+                row.extend(match run.memory {
+                    Some(stats) => [
+                        memory::format_bytes(stats.peak),
+                        memory::format_bytes(stats.total),
+                        stats.count.to_string(),
+                    ],
+                    None => Default::default(),
+                });
+            }
+            row.push(match &run.error {
+                Some(error) => format!("{}: {}", run.status, error),
+                None => run.status.to_string(),
+            });
+            return row;
+        })
+        .collect();
+    return format_columns(&header, &rows);
+}
+
+/// Lays out `rows` in left-aligned columns under `header`.
+pub fn format_columns(header: &[&str], rows: &[Vec<String>]) -> String {
+    let mut widths: Vec<usize> = header.iter().map(|h| h.chars().count()).collect();
+    for row in rows {
+        for (width, cell) in widths.iter_mut().zip(row.iter()) {
+            *width = (*width).max(cell.chars().count());
+        }
+    }
+
+    let format_row = |cells: Vec<&str>| -> String {
+        return cells
+            .iter()
+            .zip(widths.iter())
+            .map(|(cell, width)| format!("{:<width$}", cell, width = width))
+            .collect::<Vec<String>>()
+            .join(" | ")
+            .trim_end()
+            .to_string();
+    };
+
+    let mut lines: Vec<String> = Vec::new();
+    lines.push(format_row(header.to_vec()));
+    lines.push(
+        widths
+            .iter()
+            .map(|width| "-".repeat(*width))
+            .collect::<Vec<String>>()
+            .join("-+-"),
+    );
+    for row in rows {
+        lines.push(format_row(row.iter().map(|cell| cell.as_str()).collect()));
+    }
+    return lines.join("\n");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_duration() {
+        assert_eq!(format_duration(Duration::from_nanos(850)), "850ns");
+        assert_eq!(format_duration(Duration::from_micros(12)), "12µs");
+        assert_eq!(format_duration(Duration::from_micros(1_500)), "1.50ms");
+        assert_eq!(format_duration(Duration::from_millis(2_250)), "2.25s");
+    }
+
+    #[test]
+    fn test_format_table() {
+        let runs = vec![
+            PartRun {
+                year: 2023,
+                day: 1,
+                part: 2,
+                input: InputKind::Test(1),
+                answer: Some(Answer::from(281)),
+                duration: Duration::from_micros(5),
+                status: Status::Ok,
+                error: None,
+                memory: None,
+            },
+            PartRun {
+                year: 2023,
+                day: 10,
+                part: 1,
+                input: InputKind::Real,
+                answer: None,
+                duration: Duration::ZERO,
+                status: Status::MissingInput,
+                error: None,
+                memory: None,
+            },
+        ];
+        assert_eq!(
+            format_table(&runs),
+            [
+                "Day | Part | Input | Answer | Time | Status",
+                "----+------+-------+--------+------+--------------",
+                "1   | 2    | test  | 281    | 5µs  | ok",
+                "10  | 1    | real  |        |      | missing input",
+            ]
+            .join("\n")
+        );
+
+        let mut runs = runs;
+        runs[0].memory = Some(MemoryStats {
+            peak: 2048,
+            total: 4096,
+            count: 3,
+        });
+        assert_eq!(
+            format_table(&runs),
+            [
+                "Day | Part | Input | Answer | Time | Peak   | Allocated | Allocs | Status",
+                "----+------+-------+--------+------+--------+-----------+--------+--------------",
+                "1   | 2    | test  | 281    | 5µs  | 2.0KiB | 4.0KiB    | 3      | ok",
+                "10  | 1    | real  |        |      |        |           |        | missing input",
+            ]
+            .join("\n")
+        );
+    }
+    struct Panics {}
+    impl Puzzle for Panics {
+        fn solve(&self, _document: &str) -> Result<Answer, PuzzleError> {
+            let conns: Vec<i32> = Vec::new();
+            return Ok(conns[1].into());
+        }
+
+        fn solve2(&self, _document: &str) -> Result<Answer, PuzzleError> {
+            loop {
+                thread::sleep(Duration::from_millis(10));
+            }
+        }
+    }
+    static PANICS: PuzzleInfo = PuzzleInfo {
+        year: 2023,
+        day: 0,
+        title: "Panics",
+        tags: &[],
+        build: || Box::new(Panics {}),
+        build_example: None,
+    };
+
+    fn make_run(part: i32) -> PartRun {
+        return PartRun {
+            year: 2023,
+            day: 0,
+            part,
+            input: InputKind::Real,
+            answer: None,
+            duration: Duration::ZERO,
+            status: Status::MissingInput,
+            error: None,
+            memory: None,
+        };
+    }
+
+    #[test]
+    fn test_run_isolated() {
+        let hook = panic::take_hook();
+        panic::set_hook(Box::new(|_| {}));
+        let mut run = make_run(1);
+        run_isolated(&mut run, &PANICS, String::new(), None);
+        panic::set_hook(hook);
+        assert_eq!(run.status, Status::Panicked);
+        assert_eq!(
+            run.error.unwrap(),
+            "index out of bounds: the len is 0 but the index is 1"
+        );
+
+        let mut run = make_run(2);
+        run_isolated(
+            &mut run,
+            &PANICS,
+            String::new(),
+            Some(Duration::from_millis(50)),
+        );
+        assert_eq!(run.status, Status::TimedOut(Duration::from_millis(50)));
+        assert_eq!(run.status.to_string(), "timed out after 0.05s");
+    }
+
+    #[test]
+    fn test_is_failure() {
+        let with = |status: Status, input: InputKind| {
+            return PartRun {
+                status,
+                input,
+                ..make_run(1)
+            };
+        };
+        assert!(with(Status::Error, InputKind::Real).is_failure());
+        assert!(!with(Status::Error, InputKind::Test(2)).is_failure());
+        assert!(with(Status::Panicked, InputKind::Test(1)).is_failure());
+        assert!(with(Status::TimedOut(Duration::from_secs(1)), InputKind::Real).is_failure());
+        assert!(!with(Status::MissingInput, InputKind::Real).is_failure());
+        assert!(!with(Status::Ok, InputKind::Real).is_failure());
+    }
+}
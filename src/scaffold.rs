@@ -0,0 +1,161 @@
+use std::path::{Path, PathBuf};
+
+use crate::input::{InputKind, InputSource};
+use crate::registry::DEFAULT_YEAR;
+
+/// The module every new day starts from.
+const TEMPLATE: &str = include_str!("day0.rs");
+
+/// The source of a new day module, made from the `Day0Puzzle` template. Days of other years than
+/// [`DEFAULT_YEAR`] live one module deeper and register their year.
+pub fn render_day(year: i32, day: i32, title: &str) -> String {
+    let source = TEMPLATE
+        .replace("Day0Puzzle", &format!("Day{}Puzzle", day))
+        .replace("    day: 0,", &format!("    day: {},", day))
+        .replace("    title: \"\",", &format!("    title: {:?},", title));
+    if year == DEFAULT_YEAR {
+        return source;
+    }
+    return source
+        .replace("super::solve::Puzzle", "crate::solve::Puzzle")
+        .replace("    day: ", &format!("    year: {},\n    day: ", year));
+}
+
+/// Adds `pub mod <name>;` to the source of a module, keeping its module list sorted.
+pub fn add_module(source: &str, name: &str) -> Result<String, String> {
+    let declaration = format!("pub mod {};", name);
+    let mut lines: Vec<&str> = source.lines().collect();
+    if lines.contains(&declaration.as_str()) {
+        return Err(format!("module {} is already declared", name));
+    }
+    let Some(first) = lines.iter().position(|line| line.starts_with("pub mod ")) else {
+        return Err("no module declarations to add to".to_string());
+    };
+    let end = first
+        + lines[first..]
+            .iter()
+            .take_while(|line| line.starts_with("pub mod "))
+            .count();
+    let position = first
+        + lines[first..end]
+            .iter()
+            .take_while(|line| line["pub mod ".len()..].trim_end_matches(';') < name)
+            .count();
+    lines.insert(position, &declaration);
+    return Ok(lines.join("\n") + "\n");
+}
+
+fn read_source(path: &Path) -> Result<String, String> {
+    return std::fs::read_to_string(path).map_err(|err| format!("{}: {}", path.display(), err));
+}
+
+/// Creates the module of a new day under `src`, declares it and creates empty real and example
+/// inputs where they do not exist yet. Returns the files created or changed.
+///
+/// Days of [`DEFAULT_YEAR`] are declared in `src/lib.rs`. Days of other years go in `src/yYYYY`
+/// and are declared in its `mod.rs`, which is created and declared in `src/lib.rs` for the first
+/// day of a year.
+///
+/// Nothing is written if the day's module already exists.
+pub fn scaffold(src: &Path, year: i32, day: i32, title: &str) -> Result<Vec<PathBuf>, String> {
+    let year_module = format!("y{}", year);
+    let dir = match year {
+        DEFAULT_YEAR => src.to_path_buf(),
+        _ => src.join(&year_module),
+    };
+    let module_path = dir.join(format!("day{}.rs", day));
+    if module_path.exists() {
+        return Err(format!("{} already exists", module_path.display()));
+    }
+    let lib_path = src.join("lib.rs");
+    let lib = read_source(&lib_path)?;
+    let day_module = format!("day{}", day);
+    let mut sources: Vec<(PathBuf, String)> = Vec::new();
+    if year == DEFAULT_YEAR {
+        sources.push((lib_path, add_module(&lib, &day_module)?));
+    } else {
+        let mod_path = dir.join("mod.rs");
+        let year_mod = match mod_path.exists() {
+            true => add_module(&read_source(&mod_path)?, &day_module)?,
+            false => {
+                sources.push((lib_path, add_module(&lib, &year_module)?));
+                format!(
+                    "//! Solutions to Advent of Code {}.\n\npub mod {};\n",
+                    year, day_module
+                )
+            }
+        };
+        sources.push((mod_path, year_mod));
+    }
+
+    let mut written: Vec<PathBuf> = Vec::new();
+    let mut write = |path: PathBuf, contents: &str| -> Result<(), String> {
+        let result = match path.parent() {
+            Some(dir) => std::fs::create_dir_all(dir).and_then(|_| std::fs::write(&path, contents)),
+            None => std::fs::write(&path, contents),
+        };
+        result.map_err(|err| format!("{}: {}", path.display(), err))?;
+        written.push(path);
+        return Ok(());
+    };
+    write(module_path, &render_day(year, day, title))?;
+    for (path, source) in sources {
+        write(path, &source)?;
+    }
+    for kind in [InputKind::Real, InputKind::Test(1)] {
+        let path = InputSource::for_day(year, day, kind).path().unwrap();
+        if !path.exists() {
+            write(path, "")?;
+        }
+    }
+    return Ok(written);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_day() {
+        let source = render_day(2023, 21, "Step Counter");
+        assert!(source.contains("pub struct Day21Puzzle {}"));
+        assert!(source.contains("impl super::solve::Puzzle for Day21Puzzle {"));
+        assert!(source.contains("    day: 21,\n    title: \"Step Counter\",\n"));
+        assert!(!source.contains("Day0"));
+
+        let source = render_day(2024, 1, "Historian Hysteria");
+        assert!(source.contains("impl crate::solve::Puzzle for Day1Puzzle {"));
+        assert!(source.contains("    year: 2024,\n    day: 1,\n"));
+    }
+
+    #[test]
+    fn test_add_module() {
+        let lib = "//! Docs\n\npub mod bench;\npub mod day1;\npub mod day2;\npub mod registry;\n\npub use registry::PuzzleInfo;\n";
+        assert_eq!(
+            add_module(lib, "day10").unwrap(),
+            "//! Docs\n\npub mod bench;\npub mod day1;\npub mod day10;\npub mod day2;\npub mod registry;\n\npub use registry::PuzzleInfo;\n"
+        );
+        assert!(add_module(lib, "day2").is_err());
+    }
+
+    #[test]
+    fn test_scaffold_refuses_existing_day() {
+        let src = std::env::temp_dir().join(format!("aoc-scaffold-test-{}", std::process::id()));
+        std::fs::create_dir_all(&src).unwrap();
+        std::fs::write(src.join("day3.rs"), "// solved\n").unwrap();
+        std::fs::write(src.join("lib.rs"), "pub mod day3;\n").unwrap();
+
+        let err = scaffold(&src, 2023, 3, "Gear Ratios").unwrap_err();
+        assert!(err.ends_with("day3.rs already exists"));
+        assert_eq!(
+            std::fs::read_to_string(src.join("day3.rs")).unwrap(),
+            "// solved\n"
+        );
+        assert_eq!(
+            std::fs::read_to_string(src.join("lib.rs")).unwrap(),
+            "pub mod day3;\n"
+        );
+
+        std::fs::remove_dir_all(&src).unwrap();
+    }
+}
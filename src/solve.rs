@@ -1,17 +1,354 @@
-use std::io::Read;
+use std::fmt;
+use std::str::FromStr;
 
-pub fn read_puzzle_input(day: &str) -> String {
-    let mut document: String = String::new();
-    let filename: String = format!("input/{}.txt", day);
-    std::fs::File::open(filename)
-        .expect("Failed to open file")
-        .read_to_string(&mut document)
-        .expect("Failed to read file");
+use crate::visualize::Visualize;
 
-    return document;
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PuzzleError {
+    /// The input does not have the expected format. Lines and columns start at 1.
+    Parse {
+        line: usize,
+        column: usize,
+        message: String,
+    },
+    /// The input is well-formed but has no answer.
+    Unsolvable(String),
+    /// The input is a variant of the puzzle that the solver does not handle.
+    Unsupported(String),
 }
+impl PuzzleError {
+    /// A parse error at `token`, which must be a slice of `source`.
+    pub fn parse(source: &str, token: &str, message: impl Into<String>) -> PuzzleError {
+        let offset = (token.as_ptr() as usize)
+            .saturating_sub(source.as_ptr() as usize)
+            .min(source.len());
+        let before = &source[..offset];
+        let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+        return PuzzleError::Parse {
+            line: before.matches('\n').count() + 1,
+            column: before[line_start..].chars().count() + 1,
+            message: message.into(),
+        };
+    }
 
-pub trait Puzzle<T> {
-    fn solve(&self, document: &str) -> T;
-    fn solve2(&self, document: &str) -> T;
+    /// A parse error just past the end of `slice`, for when something is missing from it.
+    pub fn missing(source: &str, slice: &str, message: impl Into<String>) -> PuzzleError {
+        return PuzzleError::parse(source, &slice[slice.len()..], message);
+    }
+
+    pub fn unsolvable(message: impl Into<String>) -> PuzzleError {
+        return PuzzleError::Unsolvable(message.into());
+    }
+
+    pub fn unsupported(message: impl Into<String>) -> PuzzleError {
+        return PuzzleError::Unsupported(message.into());
+    }
+
+    /// Moves a parse error located relative to `slice` to its position in `source`, which
+    /// must contain `slice`. Other errors are returned unchanged.
+    pub fn within(self, source: &str, slice: &str) -> PuzzleError {
+        let PuzzleError::Parse {
+            line,
+            column,
+            message,
+        } = self
+        else {
+            return self;
+        };
+        let PuzzleError::Parse {
+            line: slice_line,
+            column: slice_column,
+            ..
+        } = PuzzleError::parse(source, slice, "")
+        else {
+            unreachable!();
+        };
+        return PuzzleError::Parse {
+            line: line + slice_line - 1,
+            column: match line {
+                1 => column + slice_column - 1,
+                _ => column,
+            },
+            message,
+        };
+    }
+}
+impl fmt::Display for PuzzleError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        return match self {
+            PuzzleError::Parse {
+                line,
+                column,
+                message,
+            } => write!(
+                f,
+                "parse error at line {}, column {}: {}",
+                line, column, message
+            ),
+            PuzzleError::Unsolvable(message) => write!(f, "unsolvable input: {}", message),
+            PuzzleError::Unsupported(message) => write!(f, "unsupported input: {}", message),
+        };
+    }
+}
+impl std::error::Error for PuzzleError {}
+
+/// Parses `token`, a slice of `source`, reporting a parse error at its position on failure.
+pub fn parse_token<T: FromStr>(source: &str, token: &str) -> Result<T, PuzzleError>
+where
+    T::Err: fmt::Display,
+{
+    return token.parse::<T>().map_err(|err| {
+        PuzzleError::parse(source, token, format!("invalid value `{}`: {}", token, err))
+    });
+}
+
+/// Checks that `document` is a non-empty grid whose lines all have the same length, returning
+/// its height and width.
+pub fn grid_size(document: &str) -> Result<(usize, usize), PuzzleError> {
+    let Some(first_line) = document.lines().next() else {
+        return Err(PuzzleError::parse(document, document, "empty grid"));
+    };
+    let width = first_line.chars().count();
+    for line in document.lines() {
+        if line.chars().count() != width {
+            return Err(PuzzleError::parse(
+                document,
+                line,
+                format!("expected {} columns, found {}", width, line.chars().count()),
+            ));
+        }
+    }
+    return Ok((document.lines().count(), width));
+}
+
+/// Checks that every character of `document` other than line breaks is one of `allowed`.
+pub fn check_chars(document: &str, allowed: &str) -> Result<(), PuzzleError> {
+    for (i, c) in document.char_indices() {
+        if c != '\n' && c != '\r' && !allowed.contains(c) {
+            return Err(PuzzleError::parse(
+                document,
+                &document[i..i + c.len_utf8()],
+                format!("unexpected character `{}`", c),
+            ));
+        }
+    }
+    return Ok(());
+}
+
+/// The answer to one part of a puzzle.
+///
+/// Integers compare by value whatever their variant, so an answer computed as a `usize` equals
+/// the same number parsed from a file. Parsing picks the smallest variant that holds the value
+/// and falls back to [`Answer::Text`].
+#[derive(Debug, Clone)]
+pub enum Answer {
+    Int(i64),
+    UInt(u64),
+    BigInt(i128),
+    BigUInt(u128),
+    Text(String),
+}
+impl Answer {
+    /// The sign and magnitude of an integer answer.
+    fn integer(&self) -> Option<(bool, u128)> {
+        return match *self {
+            Answer::Int(n) => Some((n < 0, n.unsigned_abs() as u128)),
+            Answer::UInt(n) => Some((false, n as u128)),
+            Answer::BigInt(n) => Some((n < 0, n.unsigned_abs())),
+            Answer::BigUInt(n) => Some((false, n)),
+            Answer::Text(_) => None,
+        };
+    }
+}
+impl fmt::Display for Answer {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        return match self {
+            Answer::Int(n) => write!(f, "{}", n),
+            Answer::UInt(n) => write!(f, "{}", n),
+            Answer::BigInt(n) => write!(f, "{}", n),
+            Answer::BigUInt(n) => write!(f, "{}", n),
+            Answer::Text(text) => write!(f, "{}", text),
+        };
+    }
+}
+impl FromStr for Answer {
+    type Err = std::convert::Infallible;
+
+    fn from_str(s: &str) -> Result<Answer, Self::Err> {
+        let s = s.trim();
+        if let Ok(n) = s.parse::<u64>() {
+            return Ok(Answer::UInt(n));
+        }
+        if let Ok(n) = s.parse::<i64>() {
+            return Ok(Answer::Int(n));
+        }
+        if let Ok(n) = s.parse::<u128>() {
+            return Ok(Answer::BigUInt(n));
+        }
+        if let Ok(n) = s.parse::<i128>() {
+            return Ok(Answer::BigInt(n));
+        }
+        return Ok(Answer::Text(s.to_string()));
+    }
+}
+impl PartialEq for Answer {
+    fn eq(&self, other: &Answer) -> bool {
+        return match (self, other) {
+            (Answer::Text(a), Answer::Text(b)) => a == b,
+            _ => self.integer().is_some() && self.integer() == other.integer(),
+        };
+    }
+}
+impl Eq for Answer {}
+impl PartialOrd for Answer {
+    /// Integers are ordered by value and text lexicographically. An integer and a text answer
+    /// are not comparable.
+    fn partial_cmp(&self, other: &Answer) -> Option<std::cmp::Ordering> {
+        if let (Answer::Text(a), Answer::Text(b)) = (self, other) {
+            return Some(a.cmp(b));
+        }
+        let (a, b) = (self.integer()?, other.integer()?);
+        return Some(match (a.0, b.0) {
+            (false, false) => a.1.cmp(&b.1),
+            (true, true) => b.1.cmp(&a.1),
+            (a_negative, b_negative) => b_negative.cmp(&a_negative),
+        });
+    }
+}
+macro_rules! impl_answer_from {
+    ($variant:ident($target:ty): $($source:ty),*) => {
+        $(
+            impl From<$source> for Answer {
+                fn from(value: $source) -> Answer {
+                    return Answer::$variant(value as $target);
+                }
+            }
+        )*
+    };
+}
+impl_answer_from!(Int(i64): i8, i16, i32, i64, isize);
+impl_answer_from!(UInt(u64): u8, u16, u32, u64, usize);
+impl_answer_from!(BigInt(i128): i128);
+impl_answer_from!(BigUInt(u128): u128);
+impl From<String> for Answer {
+    fn from(value: String) -> Answer {
+        return Answer::Text(value);
+    }
+}
+impl From<&str> for Answer {
+    fn from(value: &str) -> Answer {
+        return Answer::Text(value.to_string());
+    }
+}
+
+pub trait Puzzle {
+    fn solve(&self, document: &str) -> Result<Answer, PuzzleError>;
+    fn solve2(&self, document: &str) -> Result<Answer, PuzzleError>;
+
+    /// Whether the puzzle has a second part. Commands that run every part skip `solve2` when it
+    /// does not.
+    fn has_part2(&self) -> bool {
+        return true;
+    }
+
+    /// How to show the puzzle being solved, for the puzzles that can.
+    fn visualization(&self) -> Option<&dyn Visualize> {
+        return None;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_error_position() {
+        let document = "abc\ndef\nghi";
+        assert_eq!(
+            PuzzleError::parse(document, &document[5..7], "oops"),
+            PuzzleError::Parse {
+                line: 2,
+                column: 2,
+                message: "oops".to_string()
+            }
+        );
+        assert_eq!(
+            PuzzleError::missing(document, &document[0..3], "oops").to_string(),
+            "parse error at line 1, column 4: oops"
+        );
+    }
+
+    #[test]
+    fn test_parse_error_within() {
+        let document = "abc\ndef ghi\njkl";
+        let slice = &document[8..];
+        let err = PuzzleError::parse(slice, &slice[1..], "oops").within(document, slice);
+        assert_eq!(err.to_string(), "parse error at line 2, column 6: oops");
+        let err = PuzzleError::parse(slice, &slice[5..], "oops").within(document, slice);
+        assert_eq!(err.to_string(), "parse error at line 3, column 2: oops");
+    }
+
+    #[test]
+    fn test_grid_size() {
+        assert_eq!(grid_size("...\n.#.\n"), Ok((2, 3)));
+        assert_eq!(
+            grid_size("...\n.#").unwrap_err().to_string(),
+            "parse error at line 2, column 1: expected 3 columns, found 2"
+        );
+        assert!(grid_size("").is_err());
+    }
+
+    #[test]
+    fn test_check_chars() {
+        assert_eq!(check_chars("..\n#.\n", ".#"), Ok(()));
+        assert_eq!(
+            check_chars("..\n#x", ".#").unwrap_err().to_string(),
+            "parse error at line 2, column 2: unexpected character `x`"
+        );
+    }
+
+    #[test]
+    fn test_parse_token() {
+        let line = "Time: 7 x";
+        assert_eq!(parse_token::<i32>(line, &line[6..7]), Ok(7));
+        assert_eq!(
+            parse_token::<i32>(line, &line[8..])
+                .unwrap_err()
+                .to_string(),
+            "parse error at line 1, column 9: invalid value `x`: invalid digit found in string"
+        );
+    }
+    #[test]
+    fn test_answer_parse_and_display() {
+        assert_eq!("42".parse::<Answer>().unwrap(), Answer::UInt(42));
+        assert_eq!("-42".parse::<Answer>().unwrap(), Answer::Int(-42));
+        assert!(matches!(
+            "2000000000000000000000".parse::<Answer>().unwrap(),
+            Answer::BigUInt(_)
+        ));
+        assert!(matches!(
+            "-2000000000000000000000".parse::<Answer>().unwrap(),
+            Answer::BigInt(_)
+        ));
+        assert_eq!(" GHJK \n".parse::<Answer>().unwrap(), Answer::from("GHJK"));
+        for text in ["0", "-7", "340282366920938463463374607431768211455", "abc"] {
+            assert_eq!(text.parse::<Answer>().unwrap().to_string(), text);
+        }
+    }
+
+    #[test]
+    fn test_answer_equality() {
+        assert_eq!(Answer::from(5_usize), Answer::from(5_i32));
+        assert_eq!(Answer::from(5_u128), Answer::BigInt(5));
+        assert_ne!(Answer::from(-5_i32), Answer::from(5_u32));
+        assert_ne!(Answer::from(5_i32), Answer::from("5"));
+    }
+
+    #[test]
+    fn test_answer_ordering() {
+        assert!(Answer::from(-5_i32) < Answer::from(3_u8));
+        assert!(Answer::from(-5_i32) < Answer::from(-3_i64));
+        assert!(Answer::from(u128::MAX) > Answer::from(i64::MAX));
+        assert_eq!(Answer::from(1_i32).partial_cmp(&Answer::from("1")), None);
+    }
 }
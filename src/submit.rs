@@ -0,0 +1,339 @@
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+use crate::client::Client;
+use crate::input;
+use crate::solve::Answer;
+
+/// How the server judged a submitted answer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Verdict {
+    Correct,
+    TooHigh,
+    TooLow,
+    /// Wrong, without a hint about the direction.
+    Wrong,
+    /// Submitted too soon after a previous answer. The answer was not judged.
+    RateLimited {
+        wait: Option<Duration>,
+    },
+    /// The part is locked or was already solved, so the answer was not judged.
+    WrongLevel,
+}
+impl Verdict {
+    pub fn is_wrong(&self) -> bool {
+        return matches!(self, Verdict::TooHigh | Verdict::TooLow | Verdict::Wrong);
+    }
+
+    fn key(&self) -> &'static str {
+        return match self {
+            Verdict::Correct => "correct",
+            Verdict::TooHigh => "too-high",
+            Verdict::TooLow => "too-low",
+            Verdict::Wrong => "wrong",
+            Verdict::RateLimited { .. } => "rate-limited",
+            Verdict::WrongLevel => "wrong-level",
+        };
+    }
+
+    fn from_key(key: &str) -> Option<Verdict> {
+        return match key {
+            "correct" => Some(Verdict::Correct),
+            "too-high" => Some(Verdict::TooHigh),
+            "too-low" => Some(Verdict::TooLow),
+            "wrong" => Some(Verdict::Wrong),
+            "rate-limited" => Some(Verdict::RateLimited { wait: None }),
+            "wrong-level" => Some(Verdict::WrongLevel),
+            _ => None,
+        };
+    }
+}
+impl fmt::Display for Verdict {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        return match self {
+            Verdict::Correct => write!(f, "correct"),
+            Verdict::TooHigh => write!(f, "wrong, too high"),
+            Verdict::TooLow => write!(f, "wrong, too low"),
+            Verdict::Wrong => write!(f, "wrong"),
+            Verdict::RateLimited { wait: Some(wait) } => {
+                write!(f, "rate limited, wait {}s", wait.as_secs())
+            }
+            Verdict::RateLimited { wait: None } => write!(f, "rate limited"),
+            Verdict::WrongLevel => write!(f, "not judged, the part is locked or already solved"),
+        };
+    }
+}
+
+/// Reads the verdict out of the page the server returns for a submission.
+pub fn parse_response(page: &str) -> Result<Verdict, String> {
+    if page.contains("That's the right answer") {
+        return Ok(Verdict::Correct);
+    }
+    if page.contains("That's not the right answer") {
+        if page.contains("your answer is too high") {
+            return Ok(Verdict::TooHigh);
+        }
+        if page.contains("your answer is too low") {
+            return Ok(Verdict::TooLow);
+        }
+        return Ok(Verdict::Wrong);
+    }
+    if page.contains("You gave an answer too recently") {
+        return Ok(Verdict::RateLimited {
+            wait: parse_wait(page),
+        });
+    }
+    if page.contains("You don't seem to be solving the right level") {
+        return Ok(Verdict::WrongLevel);
+    }
+    return Err("unrecognized response from the server".to_string());
+}
+
+/// Parses the `You have 1m 5s left to wait` part of a rate limit message.
+fn parse_wait(page: &str) -> Option<Duration> {
+    let start = page.find("You have ")? + "You have ".len();
+    let end = start + page[start..].find(" left to wait")?;
+    let mut seconds = 0;
+    for amount in page[start..end].split_whitespace() {
+        let (value, unit) = amount.split_at(amount.find(|c: char| !c.is_ascii_digit())?);
+        let value = value.parse::<u64>().ok()?;
+        seconds += match unit {
+            "h" => value * 3600,
+            "m" => value * 60,
+            "s" => value,
+            _ => return None,
+        };
+    }
+    return Some(Duration::from_secs(seconds));
+}
+
+/// Posts `answer` for one part of a day and returns the verdict.
+pub fn submit(
+    client: &Client,
+    year: i32,
+    day: i32,
+    part: i32,
+    answer: &Answer,
+) -> Result<Verdict, String> {
+    let page = client.post_form(
+        &format!("{}/answer", client.day_url(year, day)),
+        &[
+            ("level", &part.to_string()),
+            ("answer", &answer.to_string()),
+        ],
+    )?;
+    return parse_response(&page);
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Attempt {
+    /// Seconds since the Unix epoch.
+    pub time: u64,
+    pub day: i32,
+    pub part: i32,
+    pub verdict: Verdict,
+    pub answer: Answer,
+}
+
+/// Every submission made so far, oldest first.
+///
+/// Stored as one whitespace-separated `<time> <day> <part> <verdict> <answer>` line per attempt,
+/// where the answer is the rest of the line.
+pub type History = Vec<Attempt>;
+
+/// The history of `year`, kept in the directory of its inputs.
+pub fn history_path(year: i32) -> PathBuf {
+    return input::year_dir(year).join("submissions.txt");
+}
+
+pub fn parse_history(text: &str) -> Result<History, String> {
+    let mut history = History::new();
+    for (i, line) in text.lines().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        let fields: Vec<&str> = line.splitn(5, ' ').collect();
+        if fields.len() != 5 {
+            return Err(format!("line {}: expected 5 fields", i + 1));
+        }
+        let invalid = |_| format!("line {}: invalid number", i + 1);
+        let Some(verdict) = Verdict::from_key(fields[3]) else {
+            return Err(format!("line {}: invalid verdict `{}`", i + 1, fields[3]));
+        };
+        history.push(Attempt {
+            time: fields[0].parse::<u64>().map_err(invalid)?,
+            day: fields[1].parse::<i32>().map_err(invalid)?,
+            part: fields[2].parse::<i32>().map_err(invalid)?,
+            verdict,
+            answer: fields[4].parse().unwrap(),
+        });
+    }
+    return Ok(history);
+}
+
+pub fn format_attempt(attempt: &Attempt) -> String {
+    return format!(
+        "{} {} {} {} {}\n",
+        attempt.time,
+        attempt.day,
+        attempt.part,
+        attempt.verdict.key(),
+        attempt.answer
+    );
+}
+
+/// Reads the history at `path`. A missing file means nothing was submitted yet.
+pub fn read_history(path: &Path) -> Result<History, String> {
+    return match std::fs::read_to_string(path) {
+        Ok(text) => parse_history(&text).map_err(|err| format!("{}: {}", path.display(), err)),
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(History::new()),
+        Err(err) => Err(format!("{}: {}", path.display(), err)),
+    };
+}
+
+pub fn append_history(path: &Path, attempt: &Attempt) -> Result<(), String> {
+    use std::io::Write;
+
+    let append = || -> std::io::Result<()> {
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        let mut file = std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)?;
+        return file.write_all(format_attempt(attempt).as_bytes());
+    };
+    return append().map_err(|err| format!("{}: {}", path.display(), err));
+}
+
+pub fn now() -> u64 {
+    return SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_secs());
+}
+
+/// Checks `answer` against earlier attempts at the same part, returning why it should not be
+/// submitted: the part is already solved, the same answer was judged wrong, or the answer is not
+/// below the lowest answer judged too high or not above the highest answer judged too low.
+pub fn check_attempt(
+    history: &History,
+    day: i32,
+    part: i32,
+    answer: &Answer,
+) -> Result<(), String> {
+    let attempts = history
+        .iter()
+        .filter(|attempt| attempt.day == day && attempt.part == part);
+    for attempt in attempts {
+        match attempt.verdict {
+            Verdict::Correct => {
+                return Err(format!("already solved with {}", attempt.answer));
+            }
+            verdict if verdict.is_wrong() && attempt.answer == *answer => {
+                return Err(format!("{} was already judged {}", answer, verdict));
+            }
+            Verdict::TooHigh if answer >= &attempt.answer => {
+                return Err(format!(
+                    "{} is not below {}, which was judged too high",
+                    answer, attempt.answer
+                ));
+            }
+            Verdict::TooLow if answer <= &attempt.answer => {
+                return Err(format!(
+                    "{} is not above {}, which was judged too low",
+                    answer, attempt.answer
+                ));
+            }
+            _ => {}
+        }
+    }
+    return Ok(());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::mock_server;
+
+    fn attempt(part: i32, verdict: Verdict, answer: &str) -> Attempt {
+        return Attempt {
+            time: 1701406800,
+            day: 1,
+            part,
+            verdict,
+            answer: answer.parse().unwrap(),
+        };
+    }
+
+    #[test]
+    fn test_parse_response() {
+        let page =
+            "<article><p>That's the right answer! You are one gold star closer.</p></article>";
+        assert_eq!(parse_response(page), Ok(Verdict::Correct));
+        let page = "That's not the right answer; your answer is too high. If you're stuck...";
+        assert_eq!(parse_response(page), Ok(Verdict::TooHigh));
+        let page = "That's not the right answer; your answer is too low.";
+        assert_eq!(parse_response(page), Ok(Verdict::TooLow));
+        let page = "That's not the right answer. If you're stuck...";
+        assert_eq!(parse_response(page), Ok(Verdict::Wrong));
+        let page = "You gave an answer too recently; you have to wait after submitting an answer \
+                    before trying again.  You have 4m 51s left to wait.";
+        assert_eq!(
+            parse_response(page),
+            Ok(Verdict::RateLimited {
+                wait: Some(Duration::from_secs(291))
+            })
+        );
+        let page = "You don't seem to be solving the right level.  Did you already complete it?";
+        assert_eq!(parse_response(page), Ok(Verdict::WrongLevel));
+        assert!(parse_response("<html>Login</html>").is_err());
+    }
+
+    #[test]
+    fn test_history_round_trip() {
+        let history = vec![
+            attempt(1, Verdict::TooLow, "54"),
+            attempt(2, Verdict::Wrong, "two words"),
+        ];
+        let text: String = history.iter().map(format_attempt).collect();
+        assert_eq!(
+            text,
+            "1701406800 1 1 too-low 54\n1701406800 1 2 wrong two words\n"
+        );
+        assert_eq!(parse_history(&text).unwrap(), history);
+        assert!(parse_history("1701406800 1 1 maybe 54").is_err());
+    }
+
+    #[test]
+    fn test_check_attempt() {
+        let history = vec![
+            attempt(1, Verdict::TooLow, "100"),
+            attempt(1, Verdict::TooHigh, "200"),
+            attempt(1, Verdict::Wrong, "150"),
+            attempt(2, Verdict::Correct, "7"),
+        ];
+        let check =
+            |part: i32, answer: &str| check_attempt(&history, 1, part, &answer.parse().unwrap());
+        assert_eq!(check(1, "120"), Ok(()));
+        assert!(check(1, "150").is_err());
+        assert!(check(1, "100").is_err());
+        assert!(check(1, "250").is_err());
+        assert!(check(1, "20").is_err());
+        assert!(check(2, "8").is_err());
+    }
+
+    #[test]
+    fn test_submit() {
+        let (url, server) = mock_server(vec![(200, "<p>That's the right answer!</p>")]);
+        let client = Client::new(&url, Some("abc".to_string()));
+        assert_eq!(
+            submit(&client, 2023, 3, 2, &Answer::from(4361)),
+            Ok(Verdict::Correct)
+        );
+        let requests = server.join().unwrap();
+        assert!(requests[0].starts_with("POST /2023/day/3/answer HTTP/1.1\r\n"));
+        assert!(requests[0].ends_with("level=2&answer=4361"));
+    }
+}
@@ -0,0 +1,176 @@
+use std::collections::BTreeMap;
+
+use crate::answers::{self, Answers};
+use crate::input::InputKind;
+use crate::runner::{PartRun, Status};
+use crate::solve::Answer;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Outcome {
+    Correct,
+    Mismatch {
+        expected: Answer,
+        actual: Answer,
+    },
+    /// An answer is stored but the part did not produce one.
+    Failed {
+        expected: Answer,
+        status: Status,
+    },
+    /// No answer is stored for this part.
+    Unchecked,
+}
+
+#[derive(Debug, Clone)]
+pub struct Check {
+    pub run: PartRun,
+    pub outcome: Outcome,
+}
+impl Check {
+    pub fn is_failure(&self) -> bool {
+        return matches!(
+            self.outcome,
+            Outcome::Mismatch { .. } | Outcome::Failed { .. }
+        );
+    }
+}
+
+pub fn check_run(expected: &Answers, run: PartRun) -> Check {
+    let outcome = match (expected.get(&run.part), &run.answer) {
+        (None, _) => Outcome::Unchecked,
+        (Some(expected), Some(actual)) if expected == actual => Outcome::Correct,
+        (Some(expected), Some(actual)) => Outcome::Mismatch {
+            expected: expected.clone(),
+            actual: actual.clone(),
+        },
+        (Some(expected), None) => Outcome::Failed {
+            expected: expected.clone(),
+            status: run.status,
+        },
+    };
+    return Check { run, outcome };
+}
+
+/// Compares every run against the answers stored next to its input.
+pub fn verify(runs: Vec<PartRun>) -> Result<Vec<Check>, String> {
+    let mut stored: BTreeMap<(i32, String), Answers> = BTreeMap::new();
+    let mut checks: Vec<Check> = Vec::new();
+    for run in runs {
+        let key = (run.year, run.input.file_stem(run.day));
+        if !stored.contains_key(&key) {
+            let answers = answers::read_answers(&answers::answers_path(key.0, &key.1))?;
+            stored.insert(key.clone(), answers);
+        }
+        checks.push(check_run(&stored[&key], run));
+    }
+    return Ok(checks);
+}
+
+/// Stores the answers of unchecked parts that ran successfully, leaving existing answers alone.
+pub fn record(checks: &[Check]) -> Result<usize, String> {
+    let mut recorded = 0;
+    let mut new_answers: BTreeMap<(i32, i32, InputKind), Vec<&PartRun>> = BTreeMap::new();
+    for check in checks {
+        if check.outcome == Outcome::Unchecked && check.run.status == Status::Ok {
+            new_answers
+                .entry((check.run.year, check.run.day, check.run.input))
+                .or_default()
+                .push(&check.run);
+        }
+    }
+
+    for ((year, day, input), runs) in new_answers {
+        let path = answers::answers_path(year, &input.file_stem(day));
+        let mut answers = answers::read_answers(&path)?;
+        for run in runs {
+            answers.insert(run.part, run.answer.clone().unwrap());
+            recorded += 1;
+        }
+        answers::write_answers(&path, &answers)
+            .map_err(|err| format!("{}: {}", path.display(), err))?;
+    }
+    return Ok(recorded);
+}
+
+pub fn format_report(checks: &[Check]) -> String {
+    let mut lines: Vec<String> = Vec::new();
+    for check in checks {
+        let run = &check.run;
+        let name = format!("day {} part {} ({})", run.day, run.part, run.input);
+        match &check.outcome {
+            Outcome::Mismatch { expected, actual } => {
+                lines.push(format!("{}: mismatch", name));
+                lines.push(format!("  - {}", expected));
+                lines.push(format!("  + {}", actual));
+            }
+            Outcome::Failed { expected, status } => match &run.error {
+                Some(error) => lines.push(format!(
+                    "{}: {}: {} (expected {})",
+                    name, status, error, expected
+                )),
+                None => lines.push(format!("{}: {} (expected {})", name, status, expected)),
+            },
+            Outcome::Correct | Outcome::Unchecked => {}
+        }
+    }
+
+    let count = |f: fn(&Outcome) -> bool| checks.iter().filter(|c| f(&c.outcome)).count();
+    lines.push(format!(
+        "{} correct, {} mismatched, {} failed, {} unchecked",
+        count(|o| matches!(o, Outcome::Correct)),
+        count(|o| matches!(o, Outcome::Mismatch { .. })),
+        count(|o| matches!(o, Outcome::Failed { .. })),
+        count(|o| matches!(o, Outcome::Unchecked)),
+    ));
+    return lines.join("\n");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    fn make_run(part: i32, answer: Option<&str>, status: Status) -> PartRun {
+        return PartRun {
+            year: 2023,
+            day: 5,
+            part,
+            input: InputKind::Real,
+            answer: answer.map(|a| a.parse().unwrap()),
+            duration: Duration::ZERO,
+            status,
+            error: None,
+            memory: None,
+        };
+    }
+
+    #[test]
+    fn test_check_run() {
+        let expected = Answers::from([(1, Answer::from(35))]);
+
+        let check = check_run(&expected, make_run(1, Some("35"), Status::Ok));
+        assert_eq!(check.outcome, Outcome::Correct);
+
+        let check = check_run(&expected, make_run(1, Some("36"), Status::Ok));
+        assert!(check.is_failure());
+
+        let check = check_run(&expected, make_run(1, None, Status::Panicked));
+        assert!(check.is_failure());
+
+        let check = check_run(&expected, make_run(2, Some("46"), Status::Ok));
+        assert_eq!(check.outcome, Outcome::Unchecked);
+    }
+
+    #[test]
+    fn test_format_report() {
+        let expected = Answers::from([(1, Answer::from(35)), (2, Answer::from(46))]);
+        let checks = vec![
+            check_run(&expected, make_run(1, Some("35"), Status::Ok)),
+            check_run(&expected, make_run(2, Some("47"), Status::Ok)),
+        ];
+        assert_eq!(
+            format_report(&checks),
+            "day 5 part 2 (real): mismatch\n  - 46\n  + 47\n1 correct, 1 mismatched, 0 failed, 0 unchecked"
+        );
+    }
+}
@@ -0,0 +1,201 @@
+use std::io::{BufRead, Write};
+use std::time::Duration;
+
+use crate::solve::PuzzleError;
+use crate::watch::CLEAR_SCREEN;
+
+/// A terminal color, drawn with the standard ANSI foreground codes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Color {
+    Red,
+    Green,
+    Yellow,
+    Blue,
+    Magenta,
+    Cyan,
+    Gray,
+}
+impl Color {
+    fn ansi_code(&self) -> u8 {
+        return match self {
+            Color::Red => 31,
+            Color::Green => 32,
+            Color::Yellow => 33,
+            Color::Blue => 34,
+            Color::Magenta => 35,
+            Color::Cyan => 36,
+            Color::Gray => 90,
+        };
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cell {
+    pub ch: char,
+    pub color: Option<Color>,
+}
+impl Cell {
+    pub const BLANK: Cell = Cell {
+        ch: ' ',
+        color: None,
+    };
+}
+
+/// One picture of a puzzle's state: a grid of characters with optional colors and a caption
+/// shown below it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Frame {
+    pub width: usize,
+    pub height: usize,
+    cells: Vec<Cell>,
+    pub caption: String,
+}
+impl Frame {
+    /// A frame filled with uncolored spaces.
+    pub fn new(width: usize, height: usize) -> Frame {
+        return Frame {
+            width,
+            height,
+            cells: vec![Cell::BLANK; width * height],
+            caption: String::new(),
+        };
+    }
+
+    /// A frame showing the lines of `grid`, padded with spaces to the longest line.
+    pub fn from_grid(grid: &str) -> Frame {
+        let width = grid
+            .lines()
+            .map(|line| line.chars().count())
+            .max()
+            .unwrap_or(0);
+        let mut frame = Frame::new(width, grid.lines().count());
+        for (row, line) in grid.lines().enumerate() {
+            for (col, ch) in line.chars().enumerate() {
+                frame.set(row, col, ch, None);
+            }
+        }
+        return frame;
+    }
+
+    pub fn with_caption(mut self, caption: impl Into<String>) -> Frame {
+        self.caption = caption.into();
+        return self;
+    }
+
+    pub fn get(&self, row: usize, col: usize) -> Cell {
+        return self.cells[row * self.width + col];
+    }
+
+    pub fn set(&mut self, row: usize, col: usize, ch: char, color: Option<Color>) {
+        self.cells[row * self.width + col] = Cell { ch, color };
+    }
+
+    /// Colors a cell, keeping its character.
+    pub fn paint(&mut self, row: usize, col: usize, color: Color) {
+        self.cells[row * self.width + col].color = Some(color);
+    }
+
+    /// Colors every cell holding `ch`.
+    pub fn paint_all(&mut self, ch: char, color: Color) {
+        for cell in self.cells.iter_mut().filter(|cell| cell.ch == ch) {
+            cell.color = Some(color);
+        }
+    }
+}
+
+/// Something that can show how it solves a puzzle, one frame at a time.
+pub trait Visualize {
+    /// The frames showing how `part` is solved on `document`, in order.
+    fn frames(&self, document: &str, part: i32) -> Result<Vec<Frame>, PuzzleError>;
+}
+
+/// Draws a frame as lines of text, coloring cells with ANSI escape codes if `color` is set.
+pub fn render(frame: &Frame, color: bool) -> String {
+    let mut text = String::new();
+    for row in 0..frame.height {
+        let mut current: Option<Color> = None;
+        for col in 0..frame.width {
+            let cell = frame.get(row, col);
+            let cell_color = cell.color.filter(|_| color);
+            if cell_color != current {
+                let code = cell_color.map_or(0, |cell_color| cell_color.ansi_code());
+                text.push_str(&format!("\x1b[{}m", code));
+                current = cell_color;
+            }
+            text.push(cell.ch);
+        }
+        if current.is_some() {
+            text.push_str("\x1b[0m");
+        }
+        text.push('\n');
+    }
+    text.push_str(&frame.caption);
+    return text;
+}
+
+/// Shows the frames one after the other, `delay` apart.
+pub fn animate(frames: &[Frame], delay: Duration, color: bool) {
+    for (i, frame) in frames.iter().enumerate() {
+        print!("{}", CLEAR_SCREEN);
+        println!("{}", render(frame, color));
+        println!("frame {}/{}", i + 1, frames.len());
+        let _ = std::io::stdout().flush();
+        std::thread::sleep(delay);
+    }
+}
+
+/// Shows one frame at a time, reading a command from `commands` after each: an empty line moves
+/// to the next frame, `b` back to the previous one, a number to that frame and `q` quits.
+pub fn step<R: BufRead>(frames: &[Frame], color: bool, mut commands: R) {
+    let mut i = 0;
+    while i < frames.len() {
+        print!("{}", CLEAR_SCREEN);
+        println!("{}", render(&frames[i], color));
+        print!(
+            "frame {}/{} (enter: next, b: back, number: jump, q: quit) ",
+            i + 1,
+            frames.len()
+        );
+        let _ = std::io::stdout().flush();
+
+        let mut command = String::new();
+        match commands.read_line(&mut command) {
+            Ok(0) | Err(_) => return,
+            Ok(_) => {}
+        }
+        i = match command.trim() {
+            "" => i + 1,
+            "b" => i.saturating_sub(1),
+            "q" => return,
+            number => match number.parse::<usize>() {
+                Ok(n @ 1..) => (n - 1).min(frames.len() - 1),
+                _ => i,
+            },
+        };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_grid() {
+        let mut frame = Frame::from_grid("#.\n.##").with_caption("step 1");
+        assert_eq!((frame.width, frame.height), (3, 2));
+        assert_eq!(frame.get(0, 2), Cell::BLANK);
+        frame.paint_all('#', Color::Gray);
+        assert_eq!(frame.get(1, 1).color, Some(Color::Gray));
+        assert_eq!(render(&frame, false), "#. \n.##\nstep 1");
+    }
+
+    #[test]
+    fn test_render_color() {
+        let mut frame = Frame::from_grid("O..\n.OO");
+        frame.paint_all('O', Color::Yellow);
+        assert_eq!(
+            render(&frame, true),
+            "\x1b[33mO\x1b[0m..\n.\x1b[33mOO\x1b[0m\n"
+        );
+    }
+}
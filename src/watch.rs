@@ -0,0 +1,135 @@
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+use crate::runner::{format_duration, PartRun, Status};
+
+/// Moves the cursor to the top left corner and clears the terminal.
+pub const CLEAR_SCREEN: &str = "\x1b[H\x1b[2J";
+
+/// What a file looked like when it was last polled: its modification time and length, or `None`
+/// if it could not be read.
+type Stamp = Option<(SystemTime, u64)>;
+
+fn stamp(path: &Path) -> Stamp {
+    let metadata = std::fs::metadata(path).ok()?;
+    return Some((metadata.modified().ok()?, metadata.len()));
+}
+
+/// Polls a file for modifications.
+pub struct Watcher {
+    path: PathBuf,
+    last: Option<Stamp>,
+}
+impl Watcher {
+    pub fn new(path: &Path) -> Watcher {
+        return Watcher {
+            path: path.to_path_buf(),
+            last: None,
+        };
+    }
+
+    pub fn path(&self) -> &Path {
+        return &self.path;
+    }
+
+    /// Whether the file was created, modified, resized or removed since the last call. The first
+    /// call always returns `true`.
+    pub fn changed(&mut self) -> bool {
+        let stamp = stamp(&self.path);
+        if self.last == Some(stamp) {
+            return false;
+        }
+        self.last = Some(stamp);
+        return true;
+    }
+
+    /// Calls `on_change` now and then every time the file changes, checking every `interval`.
+    /// Never returns.
+    pub fn watch<F: FnMut(&Path)>(&mut self, interval: Duration, mut on_change: F) -> ! {
+        loop {
+            if self.changed() {
+                on_change(&self.path);
+            }
+            std::thread::sleep(interval);
+        }
+    }
+}
+
+/// Formats the outcome of one run for the watch screen.
+pub fn format_run(run: &PartRun, source: &str) -> String {
+    let mut lines = vec![format!("Day {} part {} ({})", run.day, run.part, source)];
+    match run.status {
+        Status::Ok => {
+            lines.push(format!("{}", run.answer.as_ref().unwrap()));
+            lines.push(format!("solved in {}", format_duration(run.duration)));
+        }
+        Status::TimedOut(_) => lines.push(run.status.to_string()),
+        status => {
+            lines.push(format!(
+                "{}: {}",
+                status,
+                run.error.as_deref().unwrap_or("")
+            ));
+            if status != Status::MissingInput {
+                lines.push(format!("after {}", format_duration(run.duration)));
+            }
+        }
+    }
+    return lines.join("\n");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::input::InputKind;
+    use crate::solve::Answer;
+
+    #[test]
+    fn test_changed() {
+        let dir = std::env::temp_dir().join(format!("aoc-watch-test-{}", std::process::id()));
+        let path = dir.join("day1test.txt");
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+
+        let mut watcher = Watcher::new(&path);
+        assert!(watcher.changed());
+        assert!(!watcher.changed());
+        std::fs::write(&path, "1abc2\n").unwrap();
+        assert!(watcher.changed());
+        assert!(!watcher.changed());
+        std::fs::write(&path, "1abc2\npqr3stu8vwx\n").unwrap();
+        assert!(watcher.changed());
+        std::fs::remove_file(&path).unwrap();
+        assert!(watcher.changed());
+        assert!(!watcher.changed());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_format_run() {
+        let mut run = PartRun {
+            year: 2023,
+            day: 1,
+            part: 1,
+            input: InputKind::Test(1),
+            answer: Some(Answer::from(142)),
+            duration: Duration::from_micros(35),
+            status: Status::Ok,
+            error: None,
+            memory: None,
+        };
+        assert_eq!(
+            format_run(&run, "input/day1test.txt"),
+            "Day 1 part 1 (input/day1test.txt)\n142\nsolved in 35µs"
+        );
+        run.answer = None;
+        run.status = Status::Panicked;
+        run.error = Some("attempt to subtract with overflow".to_string());
+        assert_eq!(
+            format_run(&run, "input/day1test.txt"),
+            "Day 1 part 1 (input/day1test.txt)\npanicked: attempt to subtract with overflow\n\
+             after 35µs"
+        );
+    }
+}
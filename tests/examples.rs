@@ -0,0 +1,41 @@
+//! Solves every example in `tests/fixtures` and compares the answers against the expected ones.
+//!
+//! The tests are generated by `build.rs`, one per day, part and example. To add one, drop the
+//! example input into `tests/fixtures` as `dayNtest.txt` (or `dayNtestK.txt` for a further
+//! example) and its answers next to it as `dayNtest.answers.txt`, with one `<part>: <answer>`
+//! line per part. Examples of other years than 2023 go in a subdirectory named after the year,
+//! such as `tests/fixtures/2022`.
+
+use std::path::PathBuf;
+
+use advent_of_code_2023::answers;
+use advent_of_code_2023::input::InputKind;
+use advent_of_code_2023::registry::{self, DEFAULT_YEAR};
+use advent_of_code_2023::runner;
+
+fn fixtures_dir(year: i32) -> PathBuf {
+    let dir = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures");
+    return match year {
+        DEFAULT_YEAR => dir,
+        year => dir.join(year.to_string()),
+    };
+}
+
+fn check_example(year: i32, stem: &str, part: i32) {
+    let (day, kind) = InputKind::from_file_stem(stem).unwrap();
+    let info = registry::find(year, day)
+        .unwrap_or_else(|| panic!("day {} of {} has no solution", day, year));
+    let dir = fixtures_dir(year);
+    let document = std::fs::read_to_string(dir.join(format!("{}.txt", stem))).unwrap();
+    let expected = answers::read_answers(&dir.join(format!("{}.answers.txt", stem)))
+        .unwrap()
+        .remove(&part)
+        .unwrap();
+
+    match runner::solve_part(info.puzzle_for(kind).as_ref(), part, &document) {
+        Ok(answer) => assert_eq!(answer, expected, "{} part {}", stem, part),
+        Err(err) => panic!("{} part {}: {}", stem, part, err),
+    }
+}
+
+include!(concat!(env!("OUT_DIR"), "/example_tests.rs"));